@@ -1,4 +1,5 @@
 use crate::tools::get_input_or_panic;
+use log::{trace, warn};
 
 #[allow(dead_code)]
 pub fn day1() {
@@ -29,13 +30,11 @@ where
 {
     let mut sum: i32 = 0;
     for line in input.lines() {
-        let result = line_parser(line);
-        if result.is_some() {
-            let result = result.unwrap();
+        if let Some(result) = line_parser(line) {
             sum += result;
-            //println!("Line {} -> {} (sum: {})", line, result, sum);
+            trace!("Line {} -> {} (sum: {})", line, result, sum);
         } else {
-            println!("Line {} -> ()", line);
+            warn!("Line {} -> ()", line);
         }
     }
 
@@ -90,9 +89,8 @@ fn parse_line(line: &str) -> Option<i32> {
     let mut last: Option<i32> = None;
 
     for char in line.chars() {
-        let digit_result = digits.find(char);
-        if digit_result.is_some() {
-            let digit: Option<i32> = Some(digit_result.unwrap() as i32);
+        if let Some(digit_result) = digits.find(char) {
+            let digit: Option<i32> = Some(digit_result as i32);
             if first.is_none() {
                 first = digit;
             } else {
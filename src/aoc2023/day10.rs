@@ -1,7 +1,12 @@
-use std::collections::HashMap;
+use log::trace;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResultMultipleTests;
-use crate::tools::{Direction, Position};
+use crate::return_err_unless;
+use crate::tools::{Direction, Matrix, Point, Position};
+use crate::tools::geometry;
+use crate::tools::search::bfs;
+use crate::visualize::{frame_stride, Cell, Color, Frame, FrameSink, Visualize};
 /*
        -y
        |
@@ -11,15 +16,15 @@ use crate::tools::{Direction, Position};
        |
        +y
 
- So our lookup is nodes[y][x]!
+ The pipes are looked up as nodes[(x, y)].
 */
 struct Grid {
-    nodes: Vec<Vec<char>>,
+    nodes: Matrix<char>,
     start_position: Position,
 }
 
 impl Grid {
-    fn new(input: String) -> Grid {
+    fn new(input: String) -> Result<Grid, Error> {
         let mut outer = Vec::new();
         let mut start_position : Option<Position> = None;
 
@@ -27,7 +32,7 @@ impl Grid {
             let inner : Vec<char> = line.chars().collect();
             if let Some(s_pos_x) = inner.iter().position(|&c| c == 'S') {
                 if start_position.is_some() {
-                    panic!("Found two starting positions!");
+                    return Err(Error::parse_at(&input, line, "found a second starting position"));
                 }
                 start_position = Some(Position {
                     x: s_pos_x,
@@ -37,17 +42,13 @@ impl Grid {
             outer.push(inner);
         }
 
-        if start_position.is_none() {
-            panic!("Found no starting position!");
-        }
+        let start_position = start_position.ok_or(Error::parse("found no starting position"))?;
 
-        Grid { nodes: outer, start_position: start_position.unwrap() }
+        Ok(Grid { nodes: Matrix::create(&mut outer)?, start_position })
     }
 
     fn lookup(&self, position: &Position) -> Option<&char> {
-        self.nodes
-            .get(position.y)?
-            .get(position.x)
+        self.nodes.get_position(position)
     }
 
     fn move_checked(&self, position: &Position, direction: &Direction) -> Option<Position> {
@@ -63,12 +64,8 @@ impl Grid {
 
     fn move_unchecked(&self, position: &Position, direction: &Direction) -> Option<Position> {
         let delta = direction.to_position_delta();
-        // this will fail if we try to move below 0 on either axis
-        let new_position = position.apply(&delta)?;
-        // if this fails, we're out of bounds
-        //dbg!(format!("delta={:?} dir={:?}", delta, direction));
-        self.lookup(&new_position)?;
-        return Some(new_position);
+        trace!("delta={} dir={:?}", delta, direction);
+        self.nodes.checked_position_apply(position, &delta)
     }
 
     fn is_open_to(&self, position: &Position, direction: &Direction) -> bool {
@@ -89,166 +86,108 @@ impl Grid {
     }
 }
 
-fn find_distance_to_farthest_point(grid : &Grid) -> usize {
-    type VisitQueueEntry = (
-        Position, // The position
-        Direction, // where we came from (from the view of THIS node)
-        usize // how many steps it took
-    );
-    type VisitedEntry = (
-        Direction, // where we came from (from the view of THIS node)
-        usize // how many steps it took
-    );
-    let mut to_visit : Vec<VisitQueueEntry> = Vec::new();
-    let mut visited : HashMap<Position, VisitedEntry> = HashMap::new();
-
-    Direction::ALL.iter().for_each(|direction| {
-        if let Some(newpows) = grid.move_checked(&grid.start_position, direction) {
-            to_visit.push((newpows, direction.reverse(), 1));
-        }
-    });
-
-    while let Some(current) = to_visit.pop() {
-        let (position, entering_direction, offset) = current;
-        if let Some(other_way) = visited.get(&position) {
-            // we found the loop!
-            // We don't need to follow it, knowing the lengths of both paths is sufficient
-            let total_loop_length = offset + other_way.1;
-            assert_eq!(total_loop_length % 2, 0);
-            let farthest_point = total_loop_length / 2;
-            return farthest_point;
-        }
+/// The steps to the point of the loop farthest from the start, found by walking both ways at once.
+fn find_distance_to_farthest_point(grid: &Grid) -> Result<usize, Error> {
+    let connected = |position: &Position| -> Vec<Position> {
+        Direction::ALL
+            .iter()
+            .filter_map(|direction| grid.move_checked(position, direction))
+            .collect()
+    };
 
-        entering_direction.get_others().iter().for_each(|direction| {
-            if let Some(newpows) = grid.move_checked(&position, direction) {
-                to_visit.push((newpows, direction.reverse(), offset + 1));
-            }
-        });
-        visited.insert(position, (entering_direction, offset));
-    }
+    let visited = bfs(grid.start_position.clone(), connected, |_| false);
+    return_err_unless!(visited.len() > 1, Error::solution("found no loop through the starting position"));
 
-    unreachable!();
+    Ok(visited.nodes().map(|(_, steps)| steps).max().unwrap_or(0))
 }
 
-fn find_loop(grid : &Grid) -> HashMap<Position,Direction> {
-    type VisitQueueEntry = (
-        Position, // The position
-        Direction, // where we came from (from the view of THIS node)
-        usize // how many steps it took
-    );
-    type VisitedEntry = (
-        Direction, // where we came from (from the view of THIS node)
-        usize // how many steps it took Direction
-    );
-    let mut to_visit : Vec<VisitQueueEntry> = Vec::new();
-    let mut visited : HashMap<Position, VisitedEntry> = HashMap::new();
-
-    Direction::ALL.iter().for_each(|direction| {
-        if let Some(newpows) = grid.move_checked(&grid.start_position, direction) {
-            // setting the node to visited here is technically not perfect, since the start point
-            // could lead to three directions, but none of the inputs do actually have that scenario
-            //visited.insert(grid.start_position.clone(), (Direction::South, 0));
-            to_visit.push((newpows, direction.reverse(), 1));
+/// Follows the loop from the start position, returning its positions in walking order. Pipes that
+/// point at the start without being part of the loop are dead ends, so every way out is tried.
+fn walk_loop(grid: &Grid) -> Result<Vec<Position>, Error> {
+    Direction::ALL
+        .iter()
+        .find_map(|direction| walk_from_start(grid, *direction))
+        .ok_or(Error::solution("found no loop through the starting position"))
+}
+
+/// Walks the pipe that leaves the start position in `direction`, or `None` if it does not lead
+/// back to the start.
+fn walk_from_start(grid: &Grid, direction: Direction) -> Option<Vec<Position>> {
+    let (xsize, ysize) = grid.nodes.get_dimensions();
+    let max_length = xsize * ysize;
+    let mut path = vec![grid.start_position.clone()];
+    let mut next = grid.move_checked(&grid.start_position, &direction)?;
+    let mut came_from = direction.reverse();
+
+    while next != grid.start_position {
+        if path.len() >= max_length {
+            return None;
         }
-    });
-
-    while let Some(current) = to_visit.pop() {
-        let (position, entering_direction, offset) = current;
-        if let Some((other_diction, _)) = visited.get(&position) {
-            // we found the loop!
-            let mut result : HashMap<Position, Direction> = HashMap::new();
-
-            // first, insert the current node
-            result.insert(position.clone(), entering_direction);
-
-            let mut cursor = position.clone();
-            let mut direction = entering_direction;
-            // then find the way back to the start
-            loop {
-                let nextpos = grid.move_unchecked(&cursor, &direction.reverse());
-                if nextpos.is_none() {
-                    println!();
-                    let direction_rev = direction.reverse();
-                    grid.move_checked(&cursor, &direction_rev);
-                }
-                let nextpos = nextpos.unwrap();
-                if let Some((new_direction, _)) = visited.get(&nextpos) {
-                    result.insert(nextpos.clone(), new_direction.clone());
-                    cursor = nextpos;
-                    direction = *new_direction;
-                }
-                else {
-                    // at the start
-                    break;
-                }
-            }
+        path.push(next);
+        let cursor = path.last().unwrap();
+        let (following, direction) = Direction::ALL
+            .iter()
+            .filter(|direction| **direction != came_from)
+            .find_map(|direction| Some((grid.move_checked(cursor, direction)?, *direction)))?;
+        next = following;
+        came_from = direction.reverse();
+    }
 
-            // reset, go the other way
-            cursor = position;
-            direction = *other_diction;
-            loop {
-                let nextpos = grid.move_unchecked(&cursor, &direction.reverse()).unwrap();
-                if let Some((new_direction, _)) = visited.get(&nextpos) {
-                    result.insert(nextpos.clone(), new_direction.clone().reverse());
-                    cursor = nextpos;
-                    direction = *new_direction;
-                }
-                else {
-                    // at the start
-                    result.insert(nextpos, direction.reverse());
-                    break;
-                }
-            }
+    Some(path)
+}
 
+fn pipe_glyph(pipe: char) -> char {
+    match pipe {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        '.' => ' ',
+        c => c,
+    }
+}
 
-            return result;
+pub(crate) struct Day10;
 
+impl Visualize for Day10 {
+    fn visualize(&self, input: &str, sink: &mut dyn FrameSink) -> Result<(), Error> {
+        let grid = Grid::new(input.to_string())?;
+        let path = walk_loop(&grid)?;
+        let mut frame = Frame::from_matrix(&grid.nodes, |pipe| Cell::new(pipe_glyph(*pipe), Color::rgb(60, 60, 60)));
+
+        let stride = frame_stride(path.len(), 150);
+        for (step, position) in path.iter().enumerate() {
+            let pipe = *grid.lookup(position).unwrap();
+            let color = Color::YELLOW.blend(Color::RED, step as f64 / path.len() as f64);
+            frame.set(position.x, position.y, Cell::new(pipe_glyph(pipe), color));
+            if step % stride == 0 || step + 1 == path.len() {
+                sink.frame(&frame)?;
+            }
         }
 
-        entering_direction.get_others().iter().for_each(|direction| {
-            if let Some(newpows) = grid.move_checked(&position, direction) {
-                to_visit.push((newpows, direction.reverse(), offset + 1));
-            }
-        });
-        visited.insert(position, (entering_direction, offset));
+        Ok(())
     }
-
-    unreachable!();
 }
 
-pub(crate) struct Day10;
 impl Day for Day10 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let grid = Grid::new(input);
-        let distance = find_distance_to_farthest_point(&grid) - 1;
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let grid = Grid::new(input)?;
+        let distance = find_distance_to_farthest_point(&grid)?;
 
-        Some(distance as DayResult)
+        Ok(distance as DayResult)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let grid = Grid::new(input);
-        return None;
-        let gloop = find_loop(&grid);
-        let mut blocks_in_loop = 0;
-
-        for x in 0..grid.nodes.len() {
-            let mut in_loop = false;
-            for y in 0..grid.nodes.get(0).unwrap().len() {
-                let pos = Position { x, y };
-                if gloop.contains_key(&pos) {
-                    in_loop = !in_loop;
-                }
-                else if in_loop {
-                    blocks_in_loop += 1;
-                }
-            }
-        }
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let grid = Grid::new(input)?;
+        let path: Vec<Point> = walk_loop(&grid)?.iter().map(Point::from).collect();
 
-        Some(blocks_in_loop)
+        Ok(geometry::interior_points(&path) as DayResult)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
-        ExpectedResultMultipleTests!(vec!(8), 6733, vec!(4, 8, 10))
+        ExpectedResultMultipleTests!(vec!(8), 6733, vec!(4, 8, 10), 435)
     }
 
     fn get_year_and_date(&self) -> YearDay {
@@ -259,7 +198,27 @@ impl Day for Day10 {
         String::from("Distance to farthest point")
     }
 
-    /*fn part2_result_description(&self) -> String {
-        todo!()
-    }*/
+    fn part2_result_description(&self) -> String {
+        String::from("Tiles enclosed by the loop")
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stray_pipe_at_start() {
+        // The pipe north of the start points at it, but leads off the map
+        let input = ".|...\n.S-7.\n.|.|.\n.L-J.\n.....\n";
+        let grid = Grid::new(input.to_string()).unwrap();
+        assert_eq!(walk_loop(&grid).unwrap().len(), 8);
+
+        assert_eq!(Day10.part1(input.to_string()).unwrap(), 4);
+        assert_eq!(Day10.part2(input.to_string()).unwrap(), 1);
+    }
 }
@@ -1,4 +1,8 @@
 use crate::tools::get_input_or_panic;
+use crate::tools::parse::{
+    key_value, keyword, lines, map, pair, parse_all, preceded, separated, tag, terminated, unsigned,
+    Parser,
+};
 use std::cmp::max;
 
 struct Draw {
@@ -12,55 +16,47 @@ struct Game {
     draws: Vec<Draw>,
 }
 
-fn parse_draw(line: &str) -> Draw {
+#[derive(Copy, Clone)]
+enum Colour {
+    Red,
+    Green,
+    Blue,
+}
+
+fn draw<'a>() -> impl Parser<'a, Output = Draw> {
     // Input:
     // "2 green, 1 blue"
     // "1 red, 2 green, 4 blue"
-    let mut red: i32 = 0;
-    let mut green: i32 = 0;
-    let mut blue: i32 = 0;
-
-    let parts = line.split(',');
-    assert!(parts.clone().count() > 0); // max three colours
-    assert!(parts.clone().count() <= 3);
-
-    for part in parts {
-        // parts should now be for example ["5", "red"]
-        let mut parts = part.trim().split(' ');
-        assert_eq!(parts.clone().count(), 2);
-
-        let value: i32 = parts.next().unwrap().parse().unwrap();
-        let colour = parts.next().unwrap();
-
-        match colour {
-            "red" => red = value,
-            "green" => green = value,
-            "blue" => blue = value,
-            _ => panic!("Unkown colour: {}!", colour),
+    let colour = keyword([
+        ("red", Colour::Red),
+        ("green", Colour::Green),
+        ("blue", Colour::Blue),
+    ]);
+    let cubes = pair(terminated(unsigned::<i32>(), tag(" ")), colour);
+
+    map(separated(cubes, tag(", ")), |cubes| {
+        let mut draw = Draw {
+            red: 0,
+            green: 0,
+            blue: 0,
         };
-    }
-
-    Draw { red, green, blue }
+        for (value, colour) in cubes {
+            match colour {
+                Colour::Red => draw.red = value,
+                Colour::Green => draw.green = value,
+                Colour::Blue => draw.blue = value,
+            }
+        }
+        draw
+    })
 }
 
-fn parse_line(line: &str) -> Game {
+fn parse_games(input: &str) -> Vec<Game> {
     // "Game 68: 1 red, 3 green; 1 blue; 2 green; 3 red, 1 blue; 1 green, 3 red, 2 blue"
-    assert!(line.starts_with("Game "));
-    let mut parts = line.split(": ");
-    assert_eq!(parts.clone().count(), 2);
-    let game_topic = parts.next().unwrap();
-    let draws = parts.next().unwrap();
-
-    // parse the game id
-    // it always starts with "Game ", so we can simply cut the first 5 chars
-    let id: i32 = game_topic[5..].parse().unwrap();
-
-    // parse the draws
-    let draws = draws.split("; ");
-    assert!(draws.clone().count() > 0);
-    let draws: Vec<Draw> = draws.map(parse_draw).collect();
-
-    Game { id, draws }
+    let game = key_value(preceded(tag("Game "), unsigned()), separated(draw(), tag("; ")));
+    let game = map(game, |(id, draws)| Game { id, draws });
+
+    parse_all(input, lines(game)).unwrap_or_else(|error| panic!("{error}"))
 }
 
 fn is_valid_draw(input: &Draw, reference: &Draw) -> bool {
@@ -68,7 +64,7 @@ fn is_valid_draw(input: &Draw, reference: &Draw) -> bool {
 }
 
 fn is_valid_game(input: &Game, reference: &Draw) -> bool {
-    return input.draws.iter().all(|d| is_valid_draw(d, reference));
+    input.draws.iter().all(|d| is_valid_draw(d, reference))
 }
 
 #[allow(dead_code)]
@@ -109,8 +105,7 @@ fn day2_1() {
     };
 
     let input = get_input_or_panic("2-1", 2023);
-    let lines: Vec<&str> = input.lines().collect();
-    let games: Vec<Game> = lines.into_iter().map(parse_line).collect();
+    let games: Vec<Game> = parse_games(&input);
     let valid_games: Vec<Game> = games
         .into_iter()
         .filter(|g| is_valid_game(g, &refernce))
@@ -123,8 +118,7 @@ fn day2_1() {
 
 fn day2_2() {
     let input = get_input_or_panic("2-1", 2023);
-    let lines: Vec<&str> = input.lines().collect();
-    let games: Vec<Game> = lines.into_iter().map(parse_line).collect();
+    let games: Vec<Game> = parse_games(&input);
     let powers: Vec<i32> = games.into_iter().map(game_power).collect();
     let sum: i32 = powers.into_iter().sum();
 
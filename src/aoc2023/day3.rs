@@ -1,5 +1,6 @@
 #![allow(clippy::all)]
 use crate::tools::get_input_or_panic;
+use log::debug;
 use std::iter::{Enumerate, Peekable};
 use std::str::Chars;
 
@@ -142,8 +143,8 @@ pub fn day3_1() {
     let n_numbers = field.numbers.len();
     let n_symbols = field.symbols.len();
 
-    println!("First Symbol: {:?}", field.symbols.clone().first().unwrap());
-    println!("First Number: {:?}", field.numbers.clone().first().unwrap());
+    debug!("First Symbol: {:?}", field.symbols.first());
+    debug!("First Number: {:?}", field.numbers.first());
 
     let valid_numbers: Vec<Number> = field
         .numbers
@@ -154,7 +155,7 @@ pub fn day3_1() {
     let sum: u32 = valid_numbers.into_iter().map(|number| number.value).sum();
 
     assert_eq!(sum, 556367);
-    println!(
+    debug!(
         "Total amount of Symbols and Numbers: {} / {}",
         n_symbols, n_numbers
     );
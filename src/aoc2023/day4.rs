@@ -1,5 +1,8 @@
 #![allow(clippy::all)]
 use crate::tools::get_input_or_panic;
+use crate::tools::parse::{
+    key_value, lines, map, pair, parse_all, preceded, separated, spaces, tag, terminated, unsigned,
+};
 use std::cmp::min;
 
 #[derive(Debug, Clone)]
@@ -14,29 +17,17 @@ struct CardStack {
     card: Card,
 }
 
-fn parse_numbers_from_str(input: &str) -> Vec<u32> {
-    return input
-        .split(" ")
-        .filter(|s| !s.is_empty())
-        .map(|n| n.parse::<u32>().unwrap())
-        .collect();
-}
-
-fn parse_card(line: &str) -> Card {
+fn parse_cards(input: &str) -> Vec<Card> {
     // Input: "Card   3: 96 46 60 19 82 25 41 29 38 94 | 43 82 86 74 16 15 92 46 32  3 17 30 42 98 60 12 96 38 19 35  6 29 72 25 62"
-    //let number = &line[5..8];
-    //let number : u32 = number.trim().parse().unwrap();
-
-    let mut parts = (&line[10..]).split("|");
-    assert_eq!(parts.clone().count(), 2);
-
-    let winning_numbers = parse_numbers_from_str(parts.next().unwrap());
-    let scratched_numbers = parse_numbers_from_str(parts.next().unwrap());
-
-    Card {
+    let numbers = || preceded(spaces(), separated(unsigned::<u32>(), spaces()));
+    let id = preceded(pair(tag("Card"), spaces()), unsigned::<u32>());
+    let card = key_value(id, pair(terminated(numbers(), tag(" |")), numbers()));
+    let card = map(card, |(_, (winning_numbers, scratched_numbers))| Card {
         winning_numbers,
         scratched_numbers,
-    }
+    });
+
+    parse_all(input, lines(card)).unwrap_or_else(|error| panic!("{error}"))
 }
 
 fn card_value(card: &Card) -> u32 {
@@ -97,8 +88,7 @@ pub fn day4() {
 
 fn day4_1() {
     let input = get_input_or_panic("4-1", 2023);
-    let lines: Vec<&str> = input.lines().collect();
-    let cards: Vec<Card> = lines.into_iter().map(parse_card).collect();
+    let cards: Vec<Card> = parse_cards(&input);
     let card_values: Vec<u32> = cards.iter().map(card_value).collect();
     let sum: u32 = card_values.iter().sum();
 
@@ -108,8 +98,7 @@ fn day4_1() {
 
 fn day4_2() {
     let input = get_input_or_panic("4-1", 2023);
-    let lines: Vec<&str> = input.lines().collect();
-    let cards: Vec<Card> = lines.into_iter().map(parse_card).collect();
+    let cards: Vec<Card> = parse_cards(&input);
     let sum = find_amount_of_cards(&cards);
 
     assert_eq!(sum, 7258152);
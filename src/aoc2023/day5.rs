@@ -1,14 +1,9 @@
 #![allow(clippy::all)]
-use crate::tools::{get_input_or_panic, string_with_spaces_and_numbers_to_vec_of_numbers};
-use std::collections::HashMap;
-use std::num::ParseIntError;
-use std::str::FromStr;
-
-#[derive(Debug, Copy, Clone)]
-struct SeedRange {
-    start: i64,
-    end: i64,
-}
+use crate::error::Error;
+use crate::tools::{get_input_or_panic, FastMap};
+use crate::tools::interval::{Interval, IntervalSet, RangeMap};
+use crate::tools::sections::{read_sections, FromLine, FromSection, Repeated};
+use log::{debug, trace};
 
 #[derive(Debug)]
 struct MapperRange {
@@ -21,38 +16,18 @@ struct MapperRange {
 struct Mapper {
     from: String,
     to: String,
-    ranges: Vec<MapperRange>,
-}
-
-#[derive(Debug)]
-struct DayFiveInput {
-    seeds: Vec<i64>,
-    mapper: HashMap<String, Mapper>,
+    map: RangeMap,
 }
 
 #[derive(Debug)]
-struct SeedRangeMapResult {
-    lower: Option<SeedRange>,
-    mapped: Option<SeedRange>,
-    higher: Option<SeedRange>,
-}
+struct Seeds(Vec<i64>);
 
-#[derive(Debug)]
-struct ParseRangeError;
-impl FromStr for MapperRange {
-    type Err = ParseRangeError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl FromLine for MapperRange {
+    fn from_line(line: &str) -> Result<Self, Error> {
         // input must look like: "0 1894195346 315486903"
         // Values are destination start, source start, range
-        let numbers: Result<Vec<i64>, ParseIntError> =
-            s.split(' ').map(|s| i64::from_str_radix(s, 10)).collect();
-        if numbers.is_err() {
-            return Err(ParseRangeError);
-        }
-
-        let [start_output, start_input, range] = numbers.unwrap()[..] else {
-            return Err(ParseRangeError);
+        let [start_output, start_input, range] = Vec::<i64>::from_line(line)?[..] else {
+            return Err(Error::parse("expected destination start, source start and range"));
         };
         Ok(MapperRange {
             start_input,
@@ -63,198 +38,86 @@ impl FromStr for MapperRange {
 }
 
 impl MapperRange {
-    fn map_seed_range(&self, mut input: SeedRange) -> SeedRangeMapResult {
-        let mut result = SeedRangeMapResult {
-            lower: None,
-            mapped: None,
-            higher: None,
-        };
-        let end_input = self.start_input + self.range - 1;
-
-        if input.end < self.start_input {
-            // all values are below our range
-            result.lower = Some(input);
-            return result;
-        } else if input.start > end_input {
-            // all values are above our range
-            result.higher = Some(input);
-            return result;
-        }
-
-        if input.start < self.start_input {
-            // We know that not all values are below our input, since otherwise we would have exited
-            // in the line above
-            result.lower = Some(SeedRange {
-                start: input.start,
-                end: self.start_input - 1,
-            });
-            input.start = self.start_input
-        }
-
-        if input.end > end_input {
-            result.higher = Some(SeedRange {
-                start: end_input + 1,
-                end: input.end,
-            });
-            input.end = end_input;
-        }
-
-        result.mapped = Some(SeedRange {
-            start: input.start + self.start_output - self.start_input,
-            end: input.end + self.start_output - self.start_input,
-        });
-
-        result
-    }
-}
-
-impl Mapper {
-    fn map_range(&self, input: Vec<i64>) -> Vec<i64> {
-        return input
-            .into_iter()
-            .map(|num| {
-                for mapper in self.ranges.iter() {
-                    if num < mapper.start_input {
-                        // mappers are sorted by start input, so if we didn't match by the time our
-                        // start inputs are larger than the number, no mapper matches
-                        break;
-                    }
-                    // we now know num >= start input from the previous clause
-                    // so if we're in this non-inclusive range, we match
-                    else if num < mapper.start_input + mapper.range {
-                        return num + mapper.start_output - mapper.start_input;
-                    }
-                    // implicit no-match-continue
-                }
-                num
-            })
-            .collect();
+    fn source(&self) -> Interval {
+        Interval::with_length(self.start_input, self.range)
     }
 
-    fn map_seed_ranges(&self, input: Vec<SeedRange>) -> Vec<SeedRange> {
-        let mut result = Vec::new();
-
-        for seed_range in input.iter() {
-            let mut remainder = (*seed_range).clone();
-            for mapper in self.ranges.iter() {
-                let SeedRangeMapResult {
-                    lower,
-                    mapped,
-                    higher,
-                } = mapper.map_seed_range(remainder);
-                if lower.is_some() {
-                    // Mappers are ordered by the start value, so lower values will not be matched
-                    // by the remaining mappers
-                    result.push(lower.unwrap());
-                }
-                if mapped.is_some() {
-                    result.push(mapped.unwrap());
-                }
-                if higher.is_some() {
-                    remainder = higher.unwrap();
-                } else {
-                    // Again, since our mappers are sorted by start value, if no values remain above
-                    // the current mapper, the later mappers will never match
-                    break;
-                }
-            }
-        }
-
-        result
+    fn offset(&self) -> i64 {
+        self.start_output - self.start_input
     }
 }
 
-fn parse_mapper(lines: &mut std::str::Lines) -> Option<Mapper> {
-    let header = lines.next()?;
-    // "seed-to-soil map:" -> ["seed", "to", "soil", "map:"]
-    let header_parts = header.split(['-', ' ']);
-    let [ref from, _, ref to, _] = header_parts.map(String::from).collect::<Vec<_>>()[..] else {
-        return None;
-    };
-    let mut ranges = Vec::new();
-
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            break;
-        }
-        ranges.push(MapperRange::from_str(line).ok()?);
+impl FromSection for Seeds {
+    fn from_section(section: &str) -> Result<Self, Error> {
+        // "seeds: 79 14 55 13"
+        let numbers = section
+            .strip_prefix("seeds:")
+            .ok_or_else(|| Error::parse("expected a line like 'seeds: 79 14 55 13'"))?;
+        Vec::from_line(numbers)
+            .map(Seeds)
+            .map_err(|error| error.within(section, numbers))
     }
-
-    ranges.sort_by(|r1, r2| r1.start_input.cmp(&r2.start_input));
-    //println!("{from}-to-{to}-mapper: {:?}", ranges);
-
-    Some(Mapper {
-        from: (*(from.clone())).parse().unwrap(),
-        to: (*(to.clone())).parse().unwrap(),
-        ranges,
-    })
 }
 
-fn parse_day5_input(input: String) -> Option<DayFiveInput> {
-    let mut iter = input.lines();
-
-    let seeds: Vec<i64>;
-    {
-        let seeds_line = iter.next()?;
-        assert!(seeds_line.starts_with("seeds: "));
-        seeds = string_with_spaces_and_numbers_to_vec_of_numbers(&seeds_line[7..]).ok()?;
-    }
+impl FromSection for Mapper {
+    fn from_section(section: &str) -> Result<Self, Error> {
+        // "seed-to-soil map:" followed by the ranges
+        let (header, ranges) = section.split_once('\n').unwrap_or((section, ""));
+        let Some((from, to)) = header
+            .trim_end()
+            .strip_suffix(" map:")
+            .and_then(|name| name.split_once("-to-"))
+        else {
+            return Err(Error::parse("expected a header like 'seed-to-soil map:'"));
+        };
 
-    {
-        let empty_line = iter.next()?;
-        assert!(empty_line.is_empty());
-    }
+        let mut map = RangeMap::new();
+        let mapper_ranges = Vec::<MapperRange>::from_section(ranges).map_err(|error| error.within(section, ranges))?;
+        for (range, line) in mapper_ranges.iter().zip(ranges.lines()) {
+            if let Err(existing) = map.insert(range.source(), range.offset()) {
+                let message = format!("source range {:?} overlaps {existing:?}", range.source());
+                return Err(Error::parse_at(section, line, message));
+            }
+        }
+        trace!("{from}-to-{to}-mapper: {:?}", map);
 
-    let mut mapper_map: HashMap<String, Mapper> = HashMap::new();
-    while let Some(mapper) = parse_mapper(&mut iter) {
-        mapper_map.insert(mapper.from.clone(), mapper);
+        Ok(Mapper {
+            from: String::from(from),
+            to: String::from(to),
+            map,
+        })
     }
-
-    return Some(DayFiveInput {
-        seeds,
-        mapper: mapper_map,
-    });
 }
 
-fn seed_range_input_to_seeds(input: Vec<i64>) -> Vec<SeedRange> {
-    let mut output = Vec::with_capacity(input.capacity() / 2);
+fn parse_day5_input(input: String) -> (Vec<i64>, FastMap<String, Mapper>) {
+    let (Seeds(seeds), Repeated(mappers)): (Seeds, Repeated<Mapper>) =
+        read_sections(&input).unwrap_or_else(|error| panic!("{error}"));
 
-    let mut iter = input.iter();
-    while let Some(seed) = iter.next() {
-        let range = iter.next().unwrap();
-        output.push(SeedRange {
-            start: *seed,
-            end: seed + range - 1,
-        });
-    }
-
-    output
+    let mapper_map = mappers
+        .into_iter()
+        .map(|mapper| (mapper.from.clone(), mapper))
+        .collect();
+    (seeds, mapper_map)
 }
 
-fn run_mappers(input: DayFiveInput) -> (String, Vec<i64>) {
-    let mut category = "seed";
-    let mut seeds = input.seeds;
-    while let Some(mapper) = input.mapper.get(category) {
-        //println!("Mapping from {category} to {}", mapper.to);
-        seeds = mapper.map_range(seeds);
-        category = mapper.to.as_str();
-    }
-
-    (String::from(category), seeds)
+fn seed_range_input_to_seeds(input: Vec<i64>) -> IntervalSet {
+    input
+        .chunks(2)
+        .map(|range| Interval::with_length(range[0], range[1]))
+        .collect()
 }
 
-fn run_mappers_seed_ranges(
-    mut input: Vec<SeedRange>,
-    mapper: HashMap<String, Mapper>,
-) -> (String, Vec<SeedRange>) {
+/// Composes all mappers from seed on into a single map, returning the final category.
+fn compose_mappers(mapper: &FastMap<String, Mapper>) -> (String, RangeMap) {
     let mut category = "seed";
+    let mut map = RangeMap::new();
     while let Some(mapper) = mapper.get(category) {
-        //println!("Mapping from {category} to {}", mapper.to);
-        input = mapper.map_seed_ranges(input);
+        trace!("Mapping from {category} to {}", mapper.to);
+        map = map.then(&mapper.map);
         category = mapper.to.as_str();
     }
 
-    (String::from(category), input)
+    (String::from(category), map)
 }
 
 #[allow(dead_code)]
@@ -265,11 +128,12 @@ pub fn day5() {
 
 fn day5_1() {
     let input = get_input_or_panic("5-1", 2023);
-    let data = parse_day5_input(input).unwrap();
+    let (seeds, mapper) = parse_day5_input(input);
 
-    let (category, seeds) = run_mappers(data);
+    let (category, map) = compose_mappers(&mapper);
+    let seeds: Vec<i64> = seeds.iter().map(|seed| map.get(*seed)).collect();
 
-    println!(
+    debug!(
         "Seeds as {category} after final transformation: {:?}",
         seeds
     );
@@ -280,16 +144,16 @@ fn day5_1() {
 
 fn day5_2() {
     let input = get_input_or_panic("5-1", 2023);
-    let DayFiveInput { seeds, mapper } = parse_day5_input(input).unwrap();
+    let (seeds, mapper) = parse_day5_input(input);
 
-    let transformed_seeds = seed_range_input_to_seeds(seeds);
-    let (category, seeds) = run_mappers_seed_ranges(transformed_seeds, mapper);
+    let (category, map) = compose_mappers(&mapper);
+    let seeds = map.image(&seed_range_input_to_seeds(seeds));
 
-    println!(
+    debug!(
         "Seed ranges as {category} after final transformation: {:?}",
         seeds
     );
-    let min = seeds.iter().map(|sr| sr.start).min().unwrap();
-    //assert_eq!(min, 265018614);
+    let min = seeds.min().unwrap();
+    assert_eq!(min, 63179500);
     println!("Min {category} range mapping: {min}");
 }
@@ -1,5 +1,6 @@
 #![allow(clippy::all)]
-use crate::tools::{get_input_or_panic, string_with_spaces_and_numbers_to_vec_of_numbers};
+use crate::tools::get_input_or_panic;
+use crate::tools::parse::{line_break, pair, parse_all, preceded, separated, spaces, tag, terminated, unsigned};
 
 #[derive(Debug)]
 struct Race {
@@ -8,46 +9,30 @@ struct Race {
 }
 
 fn parse_races(input: String) -> Vec<Race> {
-    let mut lines = input.lines();
-    let times = lines.next().unwrap();
-    let distances = lines.next().unwrap();
-
-    assert!(times.starts_with("Time:       "));
-    assert!(distances.starts_with("Distance:   "));
-    assert_eq!(lines.next(), None);
-
-    let times: Vec<i64> = string_with_spaces_and_numbers_to_vec_of_numbers(&times[12..]).unwrap();
-    let distances: Vec<i64> =
-        string_with_spaces_and_numbers_to_vec_of_numbers(&distances[12..]).unwrap();
-    assert!(times.len() > 0);
-    assert_eq!(times.len(), distances.len());
-
-    let mut result = Vec::with_capacity(times.capacity());
-    let mut times_iter = times.iter();
-    let mut distances_iter = distances.iter();
+    // "Time:      7  15   30"
+    // "Distance:  9  40  200"
+    let numbers = || separated(unsigned::<i64>(), spaces());
+    let line = |name| preceded(pair(tag(name), spaces()), numbers());
+    let lines = pair(terminated(line("Time:"), line_break()), line("Distance:"));
 
-    while let Some(time) = times_iter.next() {
-        let distance = distances_iter.next().unwrap();
-        result.push(Race {
-            time: *time,
-            distance: *distance,
-        })
-    }
+    let (times, distances) = parse_all(&input, lines).unwrap_or_else(|error| panic!("{error}"));
+    assert_eq!(times.len(), distances.len());
 
-    result
+    times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| Race { time, distance })
+        .collect()
 }
 
 fn parse_race(input: String) -> Race {
-    let mut lines = input.lines();
-    let times = lines.next().unwrap();
-    let distances = lines.next().unwrap();
-
-    assert!(times.starts_with("Time:       "));
-    assert!(distances.starts_with("Distance:   "));
-    assert_eq!(lines.next(), None);
-
-    let time: i64 = times[12..].replace(" ", "").parse().unwrap();
-    let distance: i64 = distances[12..].replace(" ", "").parse().unwrap();
+    // The spaces between the numbers are just bad kerning
+    let races = parse_races(input);
+    let concat = |values: Vec<i64>| -> i64 {
+        values.iter().map(i64::to_string).collect::<String>().parse().unwrap()
+    };
+    let time = concat(races.iter().map(|race| race.time).collect());
+    let distance = concat(races.iter().map(|race| race.distance).collect());
 
     return Race { time, distance };
 }
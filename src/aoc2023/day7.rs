@@ -1,6 +1,7 @@
 #![allow(clippy::all)]
 use crate::return_none_unless;
 use crate::tools::get_input_or_panic;
+use log::trace;
 use std::cmp::Ordering;
 use std::fmt::Display;
 use std::marker::PhantomData;
@@ -288,7 +289,7 @@ fn score_hands<T: CardComparer>(hands: &mut Vec<AnalyzedHand<T>>) -> Vec<Bid> {
         .iter()
         .enumerate()
         .map(|(idx, hand)| {
-            //println!("{}", &hand);
+            trace!("{}", &hand);
             hand.bid * (idx as Bid + 1)
         })
         .collect()
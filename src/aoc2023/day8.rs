@@ -1,7 +1,9 @@
 #![allow(clippy::all)]
-use crate::tools::get_input_or_panic;
-use std::collections::HashMap;
-use std::ops::{Div, Mul, Sub};
+use crate::tools::cycle::{self, Cycle};
+use crate::tools::math;
+use crate::tools::parse::{delimited, pair, parse_all, preceded, tag, word};
+use crate::tools::{get_input_or_panic, FastMap};
+use log::debug;
 
 #[allow(dead_code)]
 pub fn day8() {
@@ -9,7 +11,7 @@ pub fn day8() {
     day8_2();
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Node {
     name: String,
     left: String,
@@ -37,18 +39,12 @@ impl TryFrom<char> for Direction {
 #[derive(Debug, Clone)]
 struct Input {
     directions: Directions,
-    nodes: HashMap<String, Node>,
+    nodes: FastMap<String, Node>,
 }
 
 #[derive(Debug, Clone)]
 struct Directions(Vec<Direction>, usize);
 
-impl Directions {
-    fn get_offset(&self) -> usize {
-        return self.1;
-    }
-}
-
 impl From<Vec<Direction>> for Directions {
     fn from(value: Vec<Direction>) -> Self {
         Directions(value, 0)
@@ -58,86 +54,57 @@ impl From<Vec<Direction>> for Directions {
 #[derive(Debug, Copy, Clone)]
 struct Path<'a> {
     current: &'a Node,
-    lookup: &'a HashMap<String, Node>,
+    lookup: &'a FastMap<String, Node>,
 }
 
 #[derive(Debug)]
 struct Loop {
-    offset: usize,
-    length: usize,
+    cycle: Cycle,
+    /// All steps at which the path is on an end node, up to the end of the first cycle.
+    end_steps: Vec<usize>,
+}
+
+impl Loop {
+    fn is_end(&self, steps: usize) -> bool {
+        self.end_steps.contains(&self.cycle.equivalent_step(steps))
+    }
+
+    /// The end steps within the cycle as `(residue, modulus)` congruences.
+    fn end_congruences(&self) -> Vec<(i128, i128)> {
+        self.end_steps
+            .iter()
+            .filter(|steps| **steps >= self.cycle.start)
+            .map(|steps| (*steps as i128, self.cycle.length as i128))
+            .collect()
+    }
 }
 
-type PathError = ();
 impl Path<'_> {
-    fn new<'a>(start: &'a Node, lookup: &'a HashMap<String, Node>) -> Path<'a> {
+    fn new<'a>(start: &'a Node, lookup: &'a FastMap<String, Node>) -> Path<'a> {
         return Path {
             current: start,
             lookup,
         };
     }
-    fn advance(&mut self, direction: Direction) -> Result<(), PathError> {
-        let next = self.current.lookup(direction, &self.lookup);
-        return if let Some(next) = next {
-            self.current = next;
-            Ok(())
-        } else {
-            Err(())
-        };
-    }
-
-    fn is_end_node(&self) -> bool {
-        return self.current.name.chars().nth(2).unwrap() == 'Z';
-    }
-
-    fn find_loop(&self, mut directions: Directions) -> Option<Loop> {
-        let mut copy = (*self).clone();
-        let limit = 100_000; // due to directions, loops can be longer than our amount of nodes
 
+    fn find_loop(&self, directions: Directions) -> Option<Loop> {
         // We found a loop when we arrived at the same node again with the same offset in the
         // pathing loop
-        let mut visited = HashMap::<(&String, usize), usize>::new();
-        let mut steps = 0;
-        let mut end_node_offset: Option<usize> = None;
-
-        // We will loop until we find the first visited node
-        while let Some(dir) = directions.next() {
-            if copy.is_end_node() {
-                if end_node_offset.is_some() {
-                    // turns out this doesn't happen, making the whole thing a lot easier
-                    panic!("Found loop with multiple end nodes!");
-                } else {
-                    end_node_offset = Some(steps);
-                }
-            }
-
-            let key = (&copy.current.name, directions.get_offset());
-            if visited.contains_key(&key) {
-                let offset = visited.get(&key).unwrap();
-                // We found the loop!
-                // The loop offset is the number of steps to the current node and the loop length is
-                // the difference from our counter to the offset. However, what we actually want is
-                // the offset to the first end node, since that's technically our first loop end.
-                if end_node_offset.is_none() {
-                    // Luckily it turns out this doesn't happen, either
-                    panic!("Found loop without end node!");
-                }
-
-                return Some(Loop {
-                    offset: end_node_offset.unwrap(),
-                    length: steps - *offset,
-                });
-            } else if steps > limit {
-                // we found no loop, give up
-                break;
-            } else {
-                visited.insert(key, steps);
-            }
-
-            Self::advance(&mut copy, dir).expect("Failed to advance!");
-            steps += 1;
-        }
-
-        panic!("Found no loop, giving up!");
+        let history = cycle::simulate((self.current, directions.1), |(node, offset)| {
+            let next = node.lookup(directions.0[*offset], self.lookup)?;
+            Some((next, (offset + 1) % directions.0.len()))
+        });
+        let cycle = history.cycle()?;
+
+        let end_steps = history
+            .states()
+            .iter()
+            .enumerate()
+            .filter(|(_, (node, _))| node.name.chars().nth(2).unwrap() == 'Z')
+            .map(|(steps, _)| steps)
+            .collect();
+
+        return Some(Loop { cycle, end_steps });
     }
 }
 
@@ -157,27 +124,21 @@ impl Iterator for Directions {
 impl Node {
     fn try_parse(value: &str) -> Option<Node> {
         // Input: "TJS = (LFP, HKT)"
-        //         0  3   7  10^13^16
-        // Sine the distance is always fixed, we can parse these via slices
-        // let's do some asserts to make sure we're right :-)
-        assert_eq!(value.len(), 16);
-        assert_eq!(&value[3..7], " = (");
-        assert_eq!(&value[10..12], ", ");
-        assert_eq!(&value[15..], ")");
-
-        // with that out of the way, things get easy!
+        let targets = delimited(tag("("), pair(word(), preceded(tag(", "), word())), tag(")"));
+        let (name, (left, right)) = parse_all(value, pair(word(), preceded(tag(" = "), targets))).ok()?;
+
         Some(Node {
-            name: String::from(&value[0..3]),
-            left: String::from(&value[7..10]),
-            right: String::from(&value[12..15]),
+            name: String::from(name),
+            left: String::from(left),
+            right: String::from(right),
         })
     }
 
     fn lookup<'a>(
         &'a self,
         direction: Direction,
-        lookup: &'a HashMap<String, Node>,
-    ) -> Option<&Node> {
+        lookup: &'a FastMap<String, Node>,
+    ) -> Option<&'a Node> {
         lookup.get(&*match direction {
             Direction::Left => &self.left,
             Direction::Right => &self.right,
@@ -199,7 +160,7 @@ fn parse_input(input: &String) -> Option<Input> {
     let next = lines.next();
     assert!(next.unwrap().is_empty());
 
-    let mut nodes = HashMap::new();
+    let mut nodes = FastMap::default();
     while let Some(node) = lines.next() {
         let node = Node::try_parse(node)?;
         nodes.insert(node.name.clone(), node);
@@ -237,46 +198,38 @@ fn follow_ghost_directions(input: Input) -> Option<usize> {
         .map(|(_, node)| Path::new(node, &input.nodes))
         .map(|path| path.find_loop(input.directions.clone()))
         .collect::<Option<Vec<Loop>>>()?;
-    //loops.iter().for_each(|lp|  println!("{:?}", lp));
-
-    // Turns out, the following is true, making the problem a lot easier:
-    assert!(loops.iter().all(|l| l.offset == l.length));
-
-    // Now we just need to find the lowest common denominator
-    let lcm = loops
-        .iter()
-        .map(|l| l.length)
-        .reduce(lowest_common_multiple)?;
-
-    Some(lcm)
-}
+    loops.iter().for_each(|lp| debug!("{:?}", lp));
 
-fn lowest_common_multiple<T>(a: T, b: T) -> T
-where
-    T: Eq,
-    T: Ord,
-    T: Sub<Output = T>,
-    T: Mul<Output = T>,
-    T: Div<Output = T>,
-    T: Copy,
-{
-    (a * b) / greatest_common_divisor(a, b)
-}
+    // Before every path is inside its cycle, just check each step
+    let all_cycling = loops.iter().map(|l| l.cycle.start).max()?;
+    if let Some(steps) = (1..all_cycling).find(|steps| loops.iter().all(|l| l.is_end(*steps))) {
+        return Some(steps);
+    }
 
-fn greatest_common_divisor<T>(mut a: T, mut b: T) -> T
-where
-    T: Eq,
-    T: Ord,
-    T: Sub<Output = T>,
-    T: Copy,
-{
-    while a != b {
-        if b > a {
-            (b, a) = (a, b);
-        }
-        a = a - b;
+    // Afterwards, each path is on an end node at `end + k * length` for any of its cycle's end
+    // nodes. Every combination of those is a system of congruences, and the earliest solution of
+    // any of them is our answer.
+    let mut combinations: Vec<Vec<(i128, i128)>> = vec![vec![]];
+    for lp in loops.iter() {
+        combinations = combinations
+            .iter()
+            .flat_map(|combination| {
+                lp.end_congruences().into_iter().map(move |congruence| {
+                    let mut combination = combination.clone();
+                    combination.push(congruence);
+                    combination
+                })
+            })
+            .collect();
     }
-    a
+
+    let all_cycling = all_cycling.max(1) as i128;
+    combinations
+        .into_iter()
+        .filter_map(math::crt)
+        .map(|(x, m)| x + ((all_cycling - x).max(0) + m - 1) / m * m)
+        .min()
+        .map(|steps| steps as usize)
 }
 
 fn day8_1() {
@@ -296,3 +249,16 @@ fn day8_2() {
     assert_eq!(steps, 16342438708751);
     println!("Number of steps: {steps}")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ghost_directions() {
+        let input = "LR\n\n11A = (11B, XXX)\n11B = (XXX, 11Z)\n11Z = (11B, XXX)\n22A = (22B, XXX)\n\
+                     22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)\nXXX = (XXX, XXX)";
+        let data = parse_input(&String::from(input)).unwrap();
+        assert_eq!(follow_ghost_directions(data), Some(6));
+    }
+}
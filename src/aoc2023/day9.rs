@@ -1,59 +1,25 @@
-use crate::tools::{get_input_or_panic, string_with_spaces_and_numbers_to_vec_of_numbers};
-use std::ops::Sub;
+use crate::tools::poly::Polynomial;
+use crate::tools::{get_input_or_panic, ints_per_line};
 
 type SensorValue = i64;
 type SensorValueList = Vec<SensorValue>;
 
-fn calculate_differences<T>(input: &Vec<T>) -> Vec<T>
-where
-    T: Sub<Output = T>,
-    T: Copy,
-{
-    assert!(input.len() >= 2);
-    let mut result = Vec::with_capacity(input.len() - 1);
-    let mut iter = input.iter();
-    let mut previous = iter.next().unwrap();
-
-    for current in iter {
-        result.push(*current - *previous);
-        previous = current;
-    }
-
-    result
+/// The sensor value at `index` of the polynomial fitted through the list, where the list starts
+/// at index 0.
+fn extrapolate_sensor_value(input: &SensorValueList, index: i64) -> SensorValue {
+    let value = Polynomial::fit_sequence(input.iter().copied()).eval(index);
+    value
+        .to_integer()
+        .and_then(|value| value.try_into().ok())
+        .unwrap_or_else(|| panic!("Extrapolated sensor value {value} is not a valid sensor value"))
 }
 
 fn extrapolate_next_sensor_value(input: &SensorValueList) -> SensorValue {
-    if input.iter().all(|value| *value == 0) {
-        return 0;
-    }
-
-    let next = calculate_differences(input);
-    /*
-    The structure is like this:
-    _ A X  <-- input
-     _ B   <-- next
-    So we get X-A=B, which we reformulate to X=A+B
-    */
-    let a = input.last().unwrap();
-    let b = extrapolate_next_sensor_value(&next);
-    *a + b
+    extrapolate_sensor_value(input, input.len() as i64)
 }
 
 fn extrapolate_previous_sensor_value(input: &SensorValueList) -> SensorValue {
-    if input.iter().all(|value| *value == 0) {
-        return 0;
-    }
-
-    let next = calculate_differences(input);
-    /*
-    The structure is like this:
-    X A _ <-- input
-     B _  <-- next
-    So we get A-X=B, which we reformulate to X=A-B
-    */
-    let a = input.first().unwrap();
-    let b = extrapolate_previous_sensor_value(&next);
-    *a - b
+    extrapolate_sensor_value(input, -1)
 }
 
 pub fn day9() {
@@ -63,27 +29,21 @@ pub fn day9() {
 
 fn day9_1() {
     let input = get_input_or_panic("9-1", 2023);
-    let sensor_values = input
-        .lines()
-        .map(string_with_spaces_and_numbers_to_vec_of_numbers)
-        .collect::<Result<Vec<SensorValueList>, _>>()
-        .expect("Failed to read sensor values!");
+    let sensor_values: Vec<SensorValueList> =
+        ints_per_line(&input).expect("Failed to read sensor values!");
     let result: SensorValue = sensor_values
         .iter()
         .map(extrapolate_next_sensor_value)
         .sum();
 
-    //assert_eq!(result, 1);
+    assert_eq!(result, 1725987467);
     println!("Sum of extrapolated sensor values: {result}");
 }
 
 fn day9_2() {
     let input = get_input_or_panic("9-1", 2023);
-    let sensor_values = input
-        .lines()
-        .map(string_with_spaces_and_numbers_to_vec_of_numbers)
-        .collect::<Result<Vec<SensorValueList>, _>>()
-        .expect("Failed to read sensor values!");
+    let sensor_values: Vec<SensorValueList> =
+        ints_per_line(&input).expect("Failed to read sensor values!");
     let result: SensorValue = sensor_values
         .iter()
         .map(extrapolate_previous_sensor_value)
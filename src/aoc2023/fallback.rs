@@ -1,5 +1,6 @@
 use std::time::Instant;
 use crate::day::{BoxedDay, Day, DayResult, ExpectedResults, Part, RunResultType, RunType, YearDay};
+use crate::error::Error;
 
 pub struct Fallback {
     year_day: YearDay,
@@ -23,12 +24,12 @@ impl Fallback {
 }
 
 impl Day for Fallback {
-    fn part1(&self, _: String) -> Option<DayResult> {
-        unimplemented!()
+    fn part1(&self, _: String) -> Result<DayResult, Error> {
+        Err(Error::Unsupported(self.year_day))
     }
 
-    fn part2(&self, _: String) -> Option<DayResult> {
-        unimplemented!()
+    fn part2(&self, _: String) -> Result<DayResult, Error> {
+        Err(Error::Unsupported(self.year_day))
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -26,8 +26,7 @@ mod fallback;
 
 pub fn get_days() -> Vec<fn()>
 {
-    let days = vec!(day1, day2, day3, day4, day5, day6, day7, day8, day9);
-    return days;
+    vec!(day1, day2, day3, day4, day5, day6, day7, day8, day9)
 }
 
 pub fn get_days_adv() -> Vec<BoxedDay> {
@@ -1,17 +1,17 @@
-use std::collections::HashMap;
-use std::hash::Hash;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
+use crate::tools::{separated_ints, IterExt};
 
 pub(crate) struct Day1;
 
 impl Day for Day1 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        Some(day1_1(input))
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        day1_1(input)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        Some(day1_2(input))
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        day1_2(input)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -34,7 +34,7 @@ impl Day for Day1 {
     }
 }
 
-fn read_input(input: String) -> (Vec<u64>, Vec<u64>) {
+fn read_input(input: &str) -> Result<(Vec<u64>, Vec<u64>), Error> {
     let lines: Vec<&str> = input.lines().collect();
     let count = lines.len();
 
@@ -42,63 +42,43 @@ fn read_input(input: String) -> (Vec<u64>, Vec<u64>) {
     let mut right: Vec<u64> = Vec::with_capacity(count);
 
     for line in lines.into_iter() {
-        let numbers = line.split_whitespace().collect::<Vec<&str>>();
-        assert_eq!(numbers.len(), 2);
-        let mut iter = numbers.into_iter();
-        left.push(iter.next().unwrap().parse().unwrap());
-        right.push(iter.next().unwrap().parse().unwrap());
+        let numbers = separated_ints(line).collect::<Result<Vec<u64>, _>>().map_err(|e| e.within(input, line))?;
+        let Some([lnum, rnum]) = numbers.into_iter().collect_array() else {
+            return Err(Error::parse_at(input, line, "expected exactly two numbers"));
+        };
+        left.push(lnum);
+        right.push(rnum);
     }
 
     left.sort();
     right.sort();
-    assert_eq!(left.len(), right.len());
 
-    return (left, right);
+    Ok((left, right))
 }
 
-fn day1_1(input: String) -> DayResult {
-    let (left, right) = read_input(input);
+fn day1_1(input: String) -> Result<DayResult, Error> {
+    let (left, right) = read_input(&input)?;
 
-    let mut ileft = left.into_iter();
-    let mut iright = right.into_iter();
     let mut result = 0u64;
-    while let Some(nleft) = ileft.next() {
-        let nright = iright.next().unwrap();
+    for (nleft, nright) in left.into_iter().zip(right) {
         result += u64::abs_diff(nright, nleft);
     }
 
-    result as DayResult
+    Ok(result as DayResult)
 }
 
-fn list_to_occurrence_map<T>(inp: &Vec<T>) -> HashMap<T, u64>
-where
-    T: Hash + Eq + Copy,
-{
-    let mut map: HashMap<T, u64> = HashMap::new();
-
-    for num in inp.into_iter() {
-        if let Some(kref) = map.get_mut(&num) {
-            *kref += 1;
-        } else {
-            map.insert(*num, 1);
-        }
-    }
-
-    map
-}
-
-fn day1_2(input: String) -> DayResult {
-    let (left, right) = read_input(input);
-    let lmap = list_to_occurrence_map(&left);
-    let rmap = list_to_occurrence_map(&right);
+fn day1_2(input: String) -> Result<DayResult, Error> {
+    let (left, right) = read_input(&input)?;
+    let lmap = left.into_iter().counts();
+    let rmap = right.into_iter().counts();
 
     let mut result = 0u64;
     for key in lmap.keys() {
         let amount_right = *lmap.get(key).unwrap();
         if let Some(amount_left) = rmap.get(key) {
-            result += *key * amount_right * *amount_left;
+            result += *key * (amount_right * *amount_left) as u64;
         }
     }
 
-    result as DayResult
+    Ok(result as DayResult)
 }
\ No newline at end of file
@@ -1,59 +1,99 @@
-use std::collections::HashSet;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResultMultipleTests;
-use crate::tools::{Direction, Matrix, Position};
+use crate::tools::{BitGrid, Connectivity, Matrix, Position};
+use crate::tools::search::{count_paths, grid_neighbours};
+use crate::visualize::{Cell, Color, Frame, FrameSink, Visualize};
 
 pub(crate) struct Day10;
 
-fn follow_trail(matrix: &Matrix<u32>, peaks: &mut HashSet<Position>, position: &Position, height: u32) -> DayResult {
-    let p1 = height + 1;
-    let mut result = 0;
-
-    for direction in Direction::ALL {
-        if let Some(nextpos) = matrix.checked_position_apply(&position, &direction.to_position_delta()) {
-            let neighbour_height = matrix.get_position(&nextpos).unwrap();
-            if neighbour_height == &p1 {
-                if p1 == 9 {
-                    peaks.insert(nextpos);
-                    result += 1;
-                } else {
-                    result += follow_trail(matrix, peaks, &nextpos, p1);
-                }
-            }
+fn parse_map(input: &str) -> Result<Matrix<u32>, Error> {
+    Matrix::from_string(input, |char| char.to_digit(10).unwrap_or(99))
+}
+
+fn trailheads(matrix: &Matrix<u32>) -> impl Iterator<Item = Position> + '_ {
+    matrix
+        .iter()
+        .filter(|(_, height)| **height == 0)
+        .map(|(pos, _)| pos)
+}
+
+/// Trails go up exactly one step at a time.
+fn uphill(matrix: &Matrix<u32>) -> impl FnMut(&Position) -> Vec<Position> + '_ {
+    grid_neighbours(matrix, Connectivity::Four, |from, to| *to == from + 1)
+}
+
+fn is_peak(matrix: &Matrix<u32>, position: &Position) -> bool {
+    matrix.get_position(position) == Some(&9)
+}
+
+/// The number of peaks reachable from `trailhead`. `seen` is scratch space, to avoid allocating it
+/// for every trailhead.
+fn reachable_peaks(matrix: &Matrix<u32>, trailhead: Position, seen: &mut BitGrid) -> usize {
+    seen.clear();
+    seen.insert(&trailhead);
+    let mut uphill = uphill(matrix);
+    let mut stack = vec![trailhead];
+    let mut peaks = 0;
+
+    while let Some(position) = stack.pop() {
+        if is_peak(matrix, &position) {
+            peaks += 1;
         }
+        stack.extend(uphill(&position).into_iter().filter(|next| seen.insert(next)));
     }
 
-    result
+    peaks
 }
 
-impl Day for Day10 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let matrix: Matrix<u32> = Matrix::from_string(&input, |char| char.to_digit(10).unwrap_or(99));
-        let mut result = 0;
-
-        for (pos, height) in matrix.into_iter() {
-            if height == &0 {
-                let mut peaks = HashSet::new();
-                follow_trail(&matrix, &mut peaks, &pos, 0);
-                result += peaks.len();
+impl Visualize for Day10 {
+    /// Shows one frame per height, highlighting every position reachable on a trail so far.
+    fn visualize(&self, input: &str, sink: &mut dyn FrameSink) -> Result<(), Error> {
+        let matrix = parse_map(input)?;
+        let glyph = |height: &u32| char::from_digit(*height, 10).unwrap_or('.');
+        let mut frame = Frame::from_matrix(&matrix, |height| Cell::new(glyph(height), Color::rgb(50, 50, 50)));
+
+        let mut reached = BitGrid::new(matrix.width(), matrix.height());
+        trailheads(&matrix).for_each(|pos| { reached.insert(&pos); });
+
+        for height in 0..=9 {
+            let color = Color::GREEN.blend(Color::WHITE, height as f64 / 9.0);
+            for pos in reached.iter() {
+                frame.set(pos.x, pos.y, Cell::new(glyph(&height), color));
             }
+            sink.frame(&frame)?;
+
+            let mut next_reached = BitGrid::new(matrix.width(), matrix.height());
+            reached
+                .iter()
+                .flat_map(|pos| matrix.neighbours(&pos, Connectivity::Four))
+                .filter(|(_, next_height)| **next_height == height + 1)
+                .for_each(|(next, _)| { next_reached.insert(&next); });
+            reached = next_reached;
         }
 
-        Some(result as DayResult)
+        Ok(())
     }
+}
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let matrix: Matrix<u32> = Matrix::from_string(&input, |char| char.to_digit(10).unwrap_or(99));
-        let mut result = 0;
+impl Day for Day10 {
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let matrix = parse_map(&input)?;
+        let mut seen = BitGrid::new(matrix.width(), matrix.height());
+        let result: usize = trailheads(&matrix)
+            .map(|trailhead| reachable_peaks(&matrix, trailhead, &mut seen))
+            .sum();
 
-        for (pos, height) in matrix.into_iter() {
-            if height == &0 {
-                let mut peaks = HashSet::new();
-                result += follow_trail(&matrix, &mut peaks, &pos, 0);
-            }
-        }
+        Ok(result as DayResult)
+    }
+
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let matrix = parse_map(&input)?;
+        let result: u64 = trailheads(&matrix)
+            .map(|trailhead| count_paths(trailhead, uphill(&matrix), |pos| is_peak(&matrix, pos)))
+            .sum();
 
-        Some(result as DayResult)
+        Ok(result as DayResult)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -71,4 +111,8 @@ impl Day for Day10 {
     fn part2_result_description(&self) -> String {
         String::from("Sum of scores of trailheads by path count")
     }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
\ No newline at end of file
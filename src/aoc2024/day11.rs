@@ -1,8 +1,9 @@
-use std::collections::HashMap;
-use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::day::{report_stats, Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
 
-use crate::tools::string_with_spaces_and_numbers_to_vec_of_numbers;
+use crate::tools::separated_ints;
+use crate::tools::memo::Memo;
 
 pub(crate) struct Day11;
 
@@ -49,7 +50,7 @@ fn split(stone: &Stone) -> Option<(Stone, Stone)> {
     // 2 -> AB div 10 10^1
     // 4 -> AABB div 100 10^2
     // 6 -> AAABBB div 1000 10^3
-    if digits % 2 == 0 {
+    if digits.is_multiple_of(2) {
         let halflen = digits / 2;
         let div = LOOKUP[(halflen - 1) as usize];
         Some((
@@ -62,45 +63,42 @@ fn split(stone: &Stone) -> Option<(Stone, Stone)> {
     }
 }
 
-fn score(stone: &Stone, remaining_blinks: u32, mut cache: &mut HashMap<(Stone, u32), DayResult>) -> DayResult {
+fn score(stone: Stone, remaining_blinks: u32, memo: &mut Memo<(Stone, u32), DayResult>) -> DayResult {
     if remaining_blinks == 0 {
         return 1;
     }
 
-    if let Some(result) = cache.get(&(*stone, remaining_blinks)) {
-        return *result;
-    }
-
-    let fscore = if stone == &0 {
-        score(&1, remaining_blinks - 1, &mut cache)
-    }
-    else if let Some((a, b)) = split(stone) {
-        score(&a, remaining_blinks - 1, &mut cache) +
-            score(&b, remaining_blinks - 1, &mut cache)
-    }
-    else {
-        score(&(stone * 2024), remaining_blinks - 1, &mut cache)
-    };
-
-    cache.insert((*stone, remaining_blinks), fscore);
-    fscore
+    memo.get_or_compute((stone, remaining_blinks), |memo| {
+        if stone == 0 {
+            score(1, remaining_blinks - 1, memo)
+        }
+        else if let Some((a, b)) = split(&stone) {
+            score(a, remaining_blinks - 1, memo) +
+                score(b, remaining_blinks - 1, memo)
+        }
+        else {
+            score(stone * 2024, remaining_blinks - 1, memo)
+        }
+    })
 }
 
 fn blink_multiple(row: StoneRow, blinks: u32) -> DayResult {
-    let mut cache = HashMap::new();
+    let mut memo = Memo::new();
 
-    row.iter().map(|s| score(s, blinks, &mut cache)).sum()
+    let result = row.iter().map(|s| score(*s, blinks, &mut memo)).sum();
+    report_stats(format_args!("Stone cache after {blinks} blinks: {}", memo.stats()));
+    result
 }
 
 impl Day for Day11 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let row : StoneRow = string_with_spaces_and_numbers_to_vec_of_numbers(&input).ok()?;
-        Some(blink_multiple(row, 25))
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let row : StoneRow = separated_ints(&input).collect::<Result<_, _>>()?;
+        Ok(blink_multiple(row, 25))
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let row : StoneRow = string_with_spaces_and_numbers_to_vec_of_numbers(&input).ok()?;
-        Some(blink_multiple(row, 75))
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let row : StoneRow = separated_ints(&input).collect::<Result<_, _>>()?;
+        Ok(blink_multiple(row, 75))
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -1,17 +1,19 @@
+use log::trace;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
-use crate::tools::{input_with_lines_with_spaces_and_numbers_to_vec_of_vec_of_numbers};
+use crate::tools::{separated_ints_per_line, IterExt};
 type Report = Vec<i64>;
 
 pub(crate) struct Day2;
 
 impl Day for Day2 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        Some(day2_1(input))
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        day2_1(input)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        Some(day2_2(input))
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        day2_2(input)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -31,8 +33,8 @@ impl Day for Day2 {
     }
 }
 
-fn day2_1(input: String) -> DayResult {
-    let reports: Vec<Report> = input_with_lines_with_spaces_and_numbers_to_vec_of_vec_of_numbers(input.as_str()).unwrap();
+fn day2_1(input: String) -> Result<DayResult, Error> {
+    let reports: Vec<Report> = separated_ints_per_line(&input)?;
 
     let mut safe = 0;
     for report in reports {
@@ -41,26 +43,27 @@ fn day2_1(input: String) -> DayResult {
         }
     }
 
-    safe as DayResult
+    Ok(safe as DayResult)
 }
 
 fn is_safe_report(report: &Report) -> bool {
     let differences: Report = report
-        .windows(2)
-        .map(|w| w[1] - w[0])
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b - a)
         .collect();
-    //print!("Report: {:?} ", report);
 
     if differences.iter().any(|d| *d == 0 || d.abs() > 3) {
-        //println!(" -> unsafe, diff");
+        trace!("Report: {:?} -> unsafe, diff", report);
         false
     } else if differences
-        .windows(2)
-        .any(|w| w[0].is_positive() != w[1].is_positive()) {
-        //println!(" -> unsafe, sign");
+        .iter()
+        .tuple_windows()
+        .any(|(a, b)| a.is_positive() != b.is_positive()) {
+        trace!("Report: {:?} -> unsafe, sign", report);
         false
     } else {
-        //println!(" -> safe");
+        trace!("Report: {:?} -> safe", report);
         true
     }
 }
@@ -68,7 +71,7 @@ fn is_safe_report(report: &Report) -> bool {
 pub fn is_safe_report_with_dampener(report: &Report) -> bool {
     if !is_safe_report(report) {
         let subreports = generate_dampened_reports(report);
-        return subreports.iter().any(is_safe_report);
+        subreports.iter().any(is_safe_report)
     } else {
         true
     }
@@ -94,8 +97,8 @@ fn generate_dampened_reports(report: &Report) -> Vec<Report> {
     rv
 }
 
-fn day2_2(input: String) -> DayResult {
-    let reports: Vec<Report> = input_with_lines_with_spaces_and_numbers_to_vec_of_vec_of_numbers(input.as_str()).unwrap();
+fn day2_2(input: String) -> Result<DayResult, Error> {
+    let reports: Vec<Report> = separated_ints_per_line(&input)?;
 
     let mut safe = 0;
     for report in reports {
@@ -104,6 +107,6 @@ fn day2_2(input: String) -> DayResult {
         }
     }
 
-    safe as DayResult
+    Ok(safe as DayResult)
 }
 
@@ -1,70 +1,65 @@
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
-use crate::tools::Matrix;
+use crate::tools::{Direction8, Matrix};
 
 pub(crate) struct Day4;
 
 impl Day for Day4 {
-    fn part1(&self, input: String) -> Option<DayResult> {
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
         let lines: Vec<&str> = input.lines().collect();
         let mut chars: Vec<Vec<char>> = lines
             .iter()
             .map(|line| line.chars().collect::<Vec<char>>())
             .collect();
 
-        let matrix = Matrix::create(&mut chars);
-        let (xsize, ysize) = matrix.get_dimensions();
+        let matrix = Matrix::create(&mut chars)?;
         let mut result = 0i128;
 
-        for x in 0..xsize {
-            for y in 0..ysize {
-                if matrix.get(x, y) == Some(&'X') {
-                    for dx in -1..2 {
-                        for dy in -1..2 {
-                            let x = x as i32;
-                            let y = y as i32;
-                            if matrix.get((x + dx) as usize, (y + dy) as usize) == Some(&'M') &&
-                                matrix.get((x + dx * 2) as usize, (y + dy * 2) as usize) == Some(&'A') &&
-                                matrix.get((x + dx * 3) as usize, (y + dy * 3) as usize) == Some(&'S') {
-                                result += 1;
-                            }
-                        }
-                    }
-                }
+        for (pos, char) in matrix.iter() {
+            if *char == 'X' {
+                result += Direction8::ALL
+                    .iter()
+                    .filter(|direction| {
+                        matrix
+                            .ray(&pos, direction.to_position_delta())
+                            .map(|(_, char)| *char)
+                            .take(3)
+                            .eq(['M', 'A', 'S'])
+                    })
+                    .count() as i128;
             }
         }
 
-        Some(result)
+        Ok(result)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
         let lines: Vec<&str> = input.lines().collect();
         let mut chars: Vec<Vec<char>> = lines
             .iter()
             .map(|line| line.chars().collect::<Vec<char>>())
             .collect();
 
-        let matrix = Matrix::create(&mut chars);
-        let (xsize, ysize) = matrix.get_dimensions();
+        let matrix = Matrix::create(&mut chars)?;
         let mut result = 0i128;
 
-        for x in 1..(xsize - 1) {
-            for y in 1..(ysize - 1) {
-                if matrix.get(x, y) == Some(&'A') {
-                    if is_valid_xmas(
-                        vec!(
-                            matrix.get(x-1, y-1),
-                            matrix.get(x+1, y-1),
-                            matrix.get(x+1, y+1),
-                            matrix.get(x-1, y+1),
-                        )) {
-                        result += 1;
-                    }
-                }
+        for (pos, char) in matrix.iter() {
+            if *char != 'A' {
+                continue;
+            }
+
+            // clockwise, so opposite corners are never next to each other
+            let corners = Direction8::DIAGONALS
+                .iter()
+                .map(|direction| matrix.ray(&pos, direction.to_position_delta()).next().map(|(_, char)| char))
+                .collect();
+            if is_valid_xmas(corners) {
+                result += 1;
             }
         }
 
-        Some(result)
+        Ok(result)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -90,10 +85,10 @@ fn is_valid_xmas(input: Vec<Option<&char>>) -> bool {
     let mut s = 0;
     for char in input.iter() {
         if let Some(char) = char {
-            match char {
-                &'M' => m += 1,
-                &'S' => s += 1,
-                &_ => { return false; }
+            match *char {
+                'M' => m += 1,
+                'S' => s += 1,
+                _ => { return false; }
             }
         } else {
             return false;
@@ -104,6 +99,6 @@ fn is_valid_xmas(input: Vec<Option<&char>>) -> bool {
         if let [i1, i2] = tpl {
             return *i1.unwrap() == *i2.unwrap();
         }
-        return false
+        false
     })
 }
\ No newline at end of file
@@ -1,7 +1,11 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use log::trace;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
+use crate::tools::FastMap;
+use crate::tools::parse::{map, pair, parse_all, preceded, tag, unsigned};
+use crate::tools::sections::{read_sections, FromLine};
 
 pub(crate) struct Day5;
 
@@ -15,42 +19,42 @@ struct OrderRule {
 }
 
 struct PrintJobVerifier {
-    rules_map: HashMap<PageNumber, Vec<PageNumber>>
+    rules_map: FastMap<PageNumber, Vec<PageNumber>>
 }
 
 impl Day for Day5 {
-    fn part1(&self, input: String) -> Option<DayResult> {
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
         let (order_rules, print_jobs) = preprocess_input(&input)?;
         let verifier = PrintJobVerifier::create(order_rules);
 
         let result: DayResult = print_jobs.iter().map(|job| {
             match verifier.is_valid_print_job(job) {
                 true => get_value_of_print_order(job),
-                false => 0,
+                false => Ok(0),
             }
-        }).sum();
+        }).sum::<Result<_, _>>()?;
 
-        Some(result)
+        Ok(result)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
         let (order_rules, print_jobs) = preprocess_input(&input)?;
         let verifier = PrintJobVerifier::create(order_rules);
 
         let result: DayResult = print_jobs.iter().map(|job| {
             match verifier.is_valid_print_job(job) {
-                true => 0,
+                true => Ok(0),
                 false => {
                     let fixed = verifier.fix_print_job(job);
-                    //dbg!(format!("Before: {:#?}", job));
-                    //dbg!(format!("After : {:#?}", fixed));
+                    trace!("Before: {:?}", job);
+                    trace!("After : {:?}", fixed);
 
                     get_value_of_print_order(&fixed)
                 },
             }
-        }).sum();
+        }).sum::<Result<_, _>>()?;
 
-        Some(result)
+        Ok(result)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -72,7 +76,7 @@ impl Day for Day5 {
 
 impl PrintJobVerifier {
     fn create(rules: Vec<OrderRule>) -> Self {
-        let mut rules_map: HashMap<PageNumber, Vec<PageNumber>> = HashMap::new();
+        let mut rules_map: FastMap<PageNumber, Vec<PageNumber>> = FastMap::default();
 
         for rule in rules {
             if let Some(v) = rules_map.get_mut(&rule.upper) {
@@ -98,7 +102,7 @@ impl PrintJobVerifier {
             }
         }
 
-        return true;
+        true
     }
 
     fn fix_print_job(&self, print_order: &PrintOrder) -> PrintOrder {
@@ -127,37 +131,23 @@ impl PrintJobVerifier {
     }
 }
 
-fn preprocess_input(input: &String) -> Option<(Vec<OrderRule>, Vec<PrintOrder>)> {
-    let results = input.split("\n\n");
-    let results: Vec<&str> = results.collect();
-    assert_eq!(results.len(), 2);
-
-    if let [ rules_raw, orders_raw ] = results[0..2] {
-        let rules: Vec<OrderRule> = rules_raw.lines().map(parse_rule).collect();
-        let orders: Vec<PrintOrder> = orders_raw.lines().map(parse_print_job).collect();
-        return Some((rules, orders));
+impl FromLine for OrderRule {
+    fn from_line(line: &str) -> Result<Self, Error> {
+        // "47|53"
+        let rule = pair(unsigned(), preceded(tag("|"), unsigned()));
+        parse_all(line, map(rule, |(lower, upper)| OrderRule { lower, upper }))
     }
-    unreachable!()
 }
 
-fn parse_rule(input: &str) -> OrderRule {
-    let results = input.split("|");
-    let results: Vec<&str> = results.collect();
-    assert_eq!(results.len(), 2);
-    OrderRule {
-        lower: results.get(0).unwrap().parse().unwrap(),
-        upper: results.get(1).unwrap().parse().unwrap(),
-    }
+fn preprocess_input(input: &str) -> Result<(Vec<OrderRule>, Vec<PrintOrder>), Error> {
+    // Print jobs are comma separated lists like "75,47,61,53,29"
+    read_sections(input)
 }
 
-fn parse_print_job(input: &str) -> PrintOrder {
-    input.split(",")
-        .map(|x| x.parse().unwrap())
-        .collect()
-}
-
-fn get_value_of_print_order(print_order: &PrintOrder) -> PageNumber {
+fn get_value_of_print_order(print_order: &PrintOrder) -> Result<PageNumber, Error> {
     let len = print_order.len();
-    assert_eq!(len % 2, 1);
-    *print_order.get(len / 2).unwrap()
+    if len % 2 != 1 {
+        return Err(Error::solution(format!("print job {print_order:?} has no middle page")));
+    }
+    Ok(print_order[len / 2])
 }
\ No newline at end of file
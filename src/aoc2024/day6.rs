@@ -1,162 +1,219 @@
-use std::collections::HashSet;
+use log::trace;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::{Error, Location, ParseError};
 use crate::ExpectedResult;
-use crate::tools::{Direction, Matrix, Position};
+use crate::return_err_unless;
+use crate::tools::{BitGrid, Direction, DirectionalBitGrid, Matrix, Position, PositionDelta};
+use crate::tools::cycle::{self, History};
+use crate::visualize::{frame_stride, Cell, Color, Frame, FrameSink, Visualize};
 
 pub(crate) struct Day6;
 
-fn walk_matrix(matrix: &Matrix<Waypoint>) -> Option<HashSet<(Position, Direction)>> {
-    let mut position = matrix.into_iter().filter (|(_, wp)| wp.sign == '^').next().unwrap().0;
-    let mut direction = Direction::North;
-    let mut visited_dupe : HashSet<(Position, Direction)> = HashSet::new();
-    visited_dupe.insert((position.clone(), direction));
-
-    while let Some(next) = matrix.checked_position_apply(&position, &direction.to_position_delta()) {
-        let space = matrix.get_position(&next).unwrap();
-
-        match space.sign {
-            '#' => {
-                direction = direction_to_right(&direction)
-            },
-            '.'|'^' => {
-                if ! visited_dupe.insert((next.clone(), direction)) {
-                    // dupe detected -> we have a loop!
-                    return None;
-                }
-                position = next;
-            },
-            c => {
-                dbg!(format!("Found invalid char {c} at {:?}!", next));
-                unimplemented!();
-            }
-        }
-    }
+fn find_start(matrix: &Matrix<char>) -> Result<Position, Error> {
+    matrix
+        .into_iter()
+        .find(|(_, sign)| **sign == '^')
+        .map(|(pos, _)| pos)
+        .ok_or(Error::parse("the map does not contain the guard ('^')"))
+}
 
-    Some(visited_dupe)
+fn invalid_sign(sign: char, position: &Position) -> Error {
+    let location = Location::new(position.y + 1, position.x + 1);
+    ParseError::new(format!("invalid sign '{sign}'")).at(Some(location)).into()
 }
 
-fn reset_matrix_waypoints(matrix: &mut Matrix<Waypoint>) {
-    let (xmax, ymax) = matrix.get_dimensions();
-    for y in 0..ymax {
-        for x in 0..xmax {
-            matrix.get_mut(x, y).unwrap().reset();
-        }
+type Guard = (Position, Direction);
+
+/// One step of the guard: either a turn or a step forward. `None` once the guard leaves the map.
+fn step_guard(matrix: &Matrix<char>, (position, direction): &Guard) -> Result<Option<Guard>, Error> {
+    let Some(next) = matrix.checked_position_apply(position, &direction.to_position_delta()) else {
+        return Ok(None);
+    };
+
+    match *matrix.get_position(&next).unwrap() {
+        '#' => Ok(Some((position.clone(), direction.turn_right()))),
+        '.'|'^' => Ok(Some((next, *direction))),
+        c => Err(invalid_sign(c, &next)),
     }
 }
 
-fn direction_to_right(direction: &Direction) -> Direction {
-    match direction {
-        Direction::North => { Direction::East }
-        Direction::East => { Direction::South }
-        Direction::South => { Direction::West }
-        Direction::West => { Direction::North }
+/// The guard's path in walking order, ending when the guard leaves the map or starts looping.
+fn walk_matrix(matrix: &Matrix<char>) -> Result<History<Guard>, Error> {
+    let mut error = None;
+    let history = cycle::simulate((find_start(matrix)?, Direction::North), |guard| {
+        step_guard(matrix, guard).unwrap_or_else(|e| {
+            error = Some(e);
+            None
+        })
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(history),
+    }
+}
+
+fn parse_map(input: &str) -> Result<Matrix<char>, Error> {
+    let matrix = Matrix::char_matrix_from_string(input)?;
+    if let Some((position, sign)) = matrix.iter().find(|(_, sign)| !matches!(sign, '#' | '.' | '^')) {
+        return Err(invalid_sign(*sign, &position));
     }
+    Ok(matrix)
 }
 
-fn check_for_loop(mut matrix: &mut Matrix<Waypoint>, starting_position: Position) -> bool {
-    let mut position = starting_position;
-    let mut direction = Direction::North;
-    let test = matrix.get_position_mut(&position).unwrap().visit(&direction);
-    assert_eq!(test, false);
-
-    while let Some(next) = matrix.checked_position_apply(&position, &direction.to_position_delta()) {
-        let space = matrix.get_position_mut(&next).unwrap();
-
-        match space.sign {
-            '#' => {
-                direction = direction_to_right(&direction)
-            },
-            '.'|'^' => {
-                if space.visit(&direction) {
-                    // found a loop!
-                    reset_matrix_waypoints(&mut matrix);
-                    return true;
-                }
-                position = next;
-            },
-            c => {
-                dbg!(format!("Found invalid char {c} at {:?}!", next));
-                unimplemented!();
+/// For each position and direction, the last position before the guard hits a wall and turns, or
+/// `None` if she walks off the map. Lets us skip straight lines in one step.
+struct Jumps {
+    stops: Matrix<[Option<Position>; 4]>,
+}
+
+impl Jumps {
+    fn new(walls: &BitGrid) -> Self {
+        let mut stops = Matrix::new(walls.width(), walls.height(), [const { None }; 4]);
+        let positions: Vec<Position> = stops.iter().map(|(pos, _)| pos).collect();
+
+        for direction in [Direction::North, Direction::West, Direction::East, Direction::South] {
+            // Make sure the position ahead is always done first
+            let ordered: Box<dyn Iterator<Item = &Position>> = match direction {
+                Direction::North | Direction::West => Box::new(positions.iter()),
+                Direction::East | Direction::South => Box::new(positions.iter().rev()),
+            };
+
+            for pos in ordered {
+                let stop = match stops.checked_position_apply(pos, &direction.to_position_delta()) {
+                    None => None,
+                    Some(ahead) if walls.contains(&ahead) => Some(pos.clone()),
+                    Some(ahead) => stops.get_position(&ahead).unwrap()[direction as usize].clone(),
+                };
+                stops.get_position_mut(pos).unwrap()[direction as usize] = stop;
             }
         }
+
+        Jumps { stops }
     }
 
-    reset_matrix_waypoints(&mut matrix);
-    false
+    fn stop(&self, position: &Position, direction: Direction) -> Option<&Position> {
+        self.stops.get_position(position).unwrap()[direction as usize].as_ref()
+    }
 }
 
-struct Waypoint {
-    sign: char,
-    visited_dir: [Option<Direction>; 4]
+/// How many steps in `direction` it takes to get from `from` to `to`, if `to` is ahead at all.
+fn distance_ahead(from: &Position, direction: Direction, to: &Position) -> Option<usize> {
+    match direction {
+        Direction::North => (to.x == from.x && to.y < from.y).then(|| from.y - to.y),
+        Direction::South => (to.x == from.x && to.y > from.y).then(|| to.y - from.y),
+        Direction::West => (to.y == from.y && to.x < from.x).then(|| from.x - to.x),
+        Direction::East => (to.y == from.y && to.x > from.x).then(|| to.x - from.x),
+    }
 }
 
-impl Waypoint {
-    fn create(sign: char) -> Self {
-        Self {
-            sign,
-            visited_dir: [ None; 4 ],
-        }
-    }
+/// Whether the guard walks in a loop if she starts at `start` and there is an additional
+/// obstacle. `turns` is scratch space, to avoid allocating it for every obstacle.
+fn loops_with_obstacle(jumps: &Jumps, obstacle: &Position, start: Guard, turns: &mut DirectionalBitGrid) -> bool {
+    turns.clear();
+    let (mut position, mut direction) = start;
+
+    loop {
+        let stop = jumps.stop(&position, direction);
+        let to_stop = stop.map(|stop| distance_ahead(&position, direction, stop).unwrap_or(0));
 
-    /// Returns whether the location was already visited from this direction
-    fn visit(&mut self, direction: &Direction) -> bool {
-        for i in 0..self.visited_dir.len() {
-            match self.visited_dir[i] {
-                None => {
-                    self.visited_dir[i] = Some(*direction);
-                    return false;
-                }
-                Some(dir) => {
-                    if &dir == direction {
-                        return true;
-                    }
-                }
+        match (distance_ahead(&position, direction, obstacle), to_stop) {
+            // the new obstacle comes before the next wall
+            (Some(to_obstacle), None) => position = steps_ahead(&position, direction, to_obstacle - 1),
+            (Some(to_obstacle), Some(to_stop)) if to_obstacle <= to_stop => {
+                position = steps_ahead(&position, direction, to_obstacle - 1)
             }
+            (_, None) => return false,
+            (_, Some(_)) => position = stop.unwrap().clone(),
         }
 
-        unreachable!();
+        // A loop repeats its turns, so it's enough to remember those
+        if !turns.insert(&position, direction) {
+            return true;
+        }
+        direction = direction.turn_right();
     }
+}
 
-    fn reset(&mut self) {
-        self.visited_dir = [ None; 4 ];
-    }
+fn steps_ahead(position: &Position, direction: Direction, steps: usize) -> Position {
+    let delta = direction.to_position_delta();
+    let delta = PositionDelta::new(delta.x * steps as isize, delta.y * steps as isize);
+    position.apply(&delta).unwrap()
 }
 
-fn unique_visited_locations(inp : HashSet<(Position, Direction)>) -> HashSet<Position> {
-    let mut rv = HashSet::new();
-    inp.iter().for_each(|(pos, _)| { rv.insert(pos.clone()); });
+fn unique_visited_locations(matrix: &Matrix<char>, inp: &[Guard]) -> BitGrid {
+    let mut rv = BitGrid::new(matrix.width(), matrix.height());
+    inp.iter().for_each(|(pos, _)| { rv.insert(pos); });
     rv
 }
 
+fn guard_glyph(direction: &Direction) -> char {
+    match direction {
+        Direction::North => '^',
+        Direction::East => '>',
+        Direction::South => 'v',
+        Direction::West => '<',
+    }
+}
+
+impl Visualize for Day6 {
+    fn visualize(&self, input: &str, sink: &mut dyn FrameSink) -> Result<(), Error> {
+        let matrix = parse_map(input)?;
+        let history = walk_matrix(&matrix)?;
+        let path = history.states();
+        let mut frame = Frame::from_matrix(&matrix, |sign| match sign {
+            '#' => Cell::new('#', Color::GREY),
+            _ => Cell::new('.', Color::rgb(40, 40, 40)),
+        });
+        let stride = frame_stride(path.len(), 200);
+
+        for (step, (position, direction)) in path.iter().enumerate() {
+            frame.set(position.x, position.y, Cell::new(guard_glyph(direction), Color::RED));
+            if step % stride == 0 || step + 1 == path.len() {
+                sink.frame(&frame)?;
+            }
+            frame.set(position.x, position.y, Cell::new('X', Color::YELLOW));
+        }
+
+        Ok(())
+    }
+}
+
 impl Day for Day6 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let matrix = Matrix::from_string(&input, Waypoint::create);
-        let visited = unique_visited_locations(walk_matrix(&matrix).unwrap());
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let matrix = parse_map(&input)?;
+        let visited = walk_matrix(&matrix)?;
+        return_err_unless!(visited.cycle().is_none(), Error::solution("the guard walks in a loop"));
+        let visited = unique_visited_locations(&matrix, visited.states());
 
-        Some(visited.len() as DayResult)
+        Ok(visited.len() as DayResult)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let mut matrix = Matrix::from_string(&input, Waypoint::create);
-        let visited = unique_visited_locations(walk_matrix(&matrix).unwrap());
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let matrix = parse_map(&input)?;
+        let path = walk_matrix(&matrix)?;
+        return_err_unless!(path.cycle().is_none(), Error::solution("the guard walks in a loop"));
+
+        let jumps = Jumps::new(&BitGrid::from_matrix(&matrix, |sign| *sign == '#'));
+        let mut tried = BitGrid::new(matrix.width(), matrix.height());
+        tried.insert(&find_start(&matrix)?);
+        let mut turns = DirectionalBitGrid::new(matrix.width(), matrix.height());
         let mut possible_loops = 0;
-        let start_position = matrix.into_iter().filter (|(_, wp)| wp.sign == '^').next().unwrap().0;
 
-        for pos in visited.iter() {
-            let pref = matrix.get_position_mut(&pos).unwrap();
-            let old = pref.sign;
-            if old == '^' {
+        for window in path.states().windows(2) {
+            let [previous, (pos, _)] = window else { unreachable!() };
+            // Only the first time the guard steps onto a position counts: an obstacle there would
+            // have stopped her before. Up to then, her path is the same, so we can start there.
+            if *pos == previous.0 || !tried.insert(pos) {
                 continue;
             }
-            pref.sign = '#';
-            if check_for_loop(&mut matrix, start_position.clone()) {
-                // if it loops, we get none here
+            if loops_with_obstacle(&jumps, pos, previous.clone(), &mut turns) {
+                trace!("An obstacle at {pos} makes the guard loop");
                 possible_loops += 1;
             }
-            matrix.get_position_mut(&pos).unwrap().sign = old;
         }
 
-        Some(possible_loops)
+        Ok(possible_loops)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -174,6 +231,10 @@ impl Day for Day6 {
     fn part2_result_description(&self) -> String {
         String::from("Number of squares where you could put an object to loop the guard")
     }
+
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
@@ -181,16 +242,29 @@ mod test{
     use super::*;
 
     #[test]
-    fn test_waypoint() {
-        let mut wp = Waypoint::create('x');
-
-        assert_eq!(wp.visited_dir, [None; 4]);
-        assert_eq!(wp.visit(&Direction::North), false);
-        assert_eq!(wp.visit(&Direction::North), true);
-        assert_eq!(wp.visit(&Direction::South), false);
-        assert_eq!(wp.visit(&Direction::South), true);
-        assert_eq!(wp.visited_dir, [ Some(Direction::North), Some(Direction::South), None, None ]);
-        wp.reset();
-        assert_eq!(wp.visited_dir, [None; 4]);
-    }
-}
\ No newline at end of file
+    fn test_loops_with_obstacle() {
+        let matrix = parse_map(".#...\n....#\n.....\n.^...\n...#.").unwrap();
+        let jumps = Jumps::new(&BitGrid::from_matrix(&matrix, |sign| *sign == '#'));
+        let mut turns = DirectionalBitGrid::new(5, 5);
+        let start = (find_start(&matrix).unwrap(), Direction::North);
+
+        assert_eq!(jumps.stop(&start.0, Direction::North), Some(&Position { x: 1, y: 1 }));
+        assert_eq!(jumps.stop(&start.0, Direction::East), None);
+        assert_eq!(jumps.stop(&Position { x: 1, y: 1 }, Direction::East), Some(&Position { x: 3, y: 1 }));
+        assert_eq!(jumps.stop(&Position { x: 3, y: 1 }, Direction::South), Some(&Position { x: 3, y: 3 }));
+
+        // Without an obstacle at (0, 3), the guard leaves to the west after three turns
+        assert!(!loops_with_obstacle(&jumps, &Position { x: 4, y: 4 }, start.clone(), &mut turns));
+        assert!(loops_with_obstacle(&jumps, &Position { x: 0, y: 3 }, start.clone(), &mut turns));
+        // Blocking the way north right away
+        assert!(!loops_with_obstacle(&jumps, &Position { x: 1, y: 2 }, start.clone(), &mut turns));
+    }
+
+    #[test]
+    fn test_invalid_sign() {
+        match parse_map("..#\n.^x\n") {
+            Err(Error::Parse(error)) => assert_eq!(error.location, Some(Location::new(2, 3))),
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+}
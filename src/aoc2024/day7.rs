@@ -1,32 +1,22 @@
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
+use crate::tools::parse::{key_value, lines, map, parse_all, separated, spaces, unsigned};
 
 pub(crate) struct Day7;
 
 impl Day for Day7 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let lines: Vec<Vec<u64>> = input.lines()
-            .map(|line| {
-                line.split(&[' ', ':'])
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.parse().unwrap())
-                    .collect()
-            }).collect();
-
-        Some(lines.iter().map(is_valid_line).sum::<u64>() as DayResult)
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let lines = parse_equations(&input)?;
+
+        Ok(lines.iter().map(|line| is_valid_line(line)).sum::<u64>() as DayResult)
     }
 
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let lines: Vec<Vec<u64>> = input.lines()
-            .map(|line| {
-                line.split(&[' ', ':'])
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.parse().unwrap())
-                    .collect()
-            }).collect();
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let lines = parse_equations(&input)?;
 
-        Some(lines.iter().map(is_valid_line2).sum::<u64>() as DayResult)
+        Ok(lines.iter().map(|line| is_valid_line2(line)).sum::<u64>() as DayResult)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -46,51 +36,61 @@ impl Day for Day7 {
     }
 }
 
-fn is_valid_line(line: &Vec<u64>) -> u64 {
+fn parse_equations(input: &str) -> Result<Vec<Vec<u64>>, Error> {
+    // "3267: 81 40 27" -> [3267, 81, 40, 27]
+    let equation = key_value(unsigned(), separated(unsigned(), spaces()));
+    let equation = map(equation, |(result, mut numbers): (u64, Vec<u64>)| {
+        numbers.insert(0, result);
+        numbers
+    });
+    parse_all(input, lines(equation))
+}
+
+fn is_valid_line(line: &[u64]) -> u64 {
     let mut iter = line.iter();
     let value = iter.next().unwrap();
     let first = iter.next().unwrap();
 
-    if is_valid_line_iter(*value, *first, &iter.map(|e| *e).collect()) {
+    if is_valid_line_iter(*value, *first, iter.as_slice()) {
         return *value;
     }
-    return 0;
+    0
 }
 
-fn is_valid_line_iter(expected: u64, sum: u64, rest: &Vec<u64>) -> bool {
-    if rest.len() == 0 {
+fn is_valid_line_iter(expected: u64, sum: u64, rest: &[u64]) -> bool {
+    if rest.is_empty() {
         return expected == sum;
     }
     let mut iter = rest.iter();
     let current = iter.next().unwrap();
-    let rest: Vec<u64> = iter.map(|x| *x).collect();
+    let rest = iter.as_slice();
 
-    is_valid_line_iter(expected, sum + current, &rest) || is_valid_line_iter(expected, sum * current, &rest)
+    is_valid_line_iter(expected, sum + current, rest) || is_valid_line_iter(expected, sum * current, rest)
 }
 
 
-fn is_valid_line2(line: &Vec<u64>) -> u64 {
+fn is_valid_line2(line: &[u64]) -> u64 {
     let mut iter = line.iter();
     let value = iter.next().unwrap();
     let first = iter.next().unwrap();
 
-    if is_valid_line_iter2(*value, *first, &iter.map(|e| *e).collect()) {
+    if is_valid_line_iter2(*value, *first, iter.as_slice()) {
         return *value;
     }
-    return 0;
+    0
 }
 
-fn is_valid_line_iter2(expected: u64, sum: u64, rest: &Vec<u64>) -> bool {
-    if rest.len() == 0 {
+fn is_valid_line_iter2(expected: u64, sum: u64, rest: &[u64]) -> bool {
+    if rest.is_empty() {
         return expected == sum;
     }
     let mut iter = rest.iter();
     let current = iter.next().unwrap();
-    let rest: Vec<u64> = iter.map(|x| *x).collect();
+    let rest = iter.as_slice();
 
-    is_valid_line_iter2(expected, sum + current, &rest)
-        || is_valid_line_iter2(expected, sum * current, &rest)
-        || is_valid_line_iter2(expected, concat_numbers(sum, *current), &rest)
+    is_valid_line_iter2(expected, sum + current, rest)
+        || is_valid_line_iter2(expected, sum * current, rest)
+        || is_valid_line_iter2(expected, concat_numbers(sum, *current), rest)
 }
 
 fn concat_numbers(a: u64, b: u64) -> u64 {
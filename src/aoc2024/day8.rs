@@ -1,13 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use log::{log_enabled, trace, Level};
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResultMultipleTests;
-use crate::tools::{Matrix, Position, PositionDelta};
+use crate::tools::{FastMap, FastSet, IterExt, Matrix, Point, Position};
 
 pub(crate) struct Day8;
 
-fn input_to_antennas_and_matrix(input: &String) -> (Matrix<char>, HashMap<char, Vec<Position>>) {
-    let matrix = Matrix::char_matrix_from_string(&input);
-    let mut antennas: HashMap<char, Vec<Position>> = HashMap::new();
+type Antennas = FastMap<char, Vec<Position>>;
+
+fn input_to_antennas_and_matrix(input: &str) -> Result<(Matrix<char>, Antennas), Error> {
+    let matrix = Matrix::char_matrix_from_string(input)?;
+    let mut antennas: Antennas = FastMap::default();
 
     for (pos, sign) in matrix.into_iter() {
         if *sign != '.' {
@@ -19,69 +22,58 @@ fn input_to_antennas_and_matrix(input: &String) -> (Matrix<char>, HashMap<char,
         }
     }
 
-    (matrix, antennas)
-}
-
-fn iter_while_valid_position<T>(matrix: &Matrix<T>, nodes: &mut HashSet<Position>,start: &Position, direction: &PositionDelta) {
-    if let Some(next) = matrix.checked_position_apply(start, direction) {
-        iter_while_valid_position(matrix, nodes, &next, direction);
-        nodes.insert(next);
-    }
+    Ok((matrix, antennas))
 }
 
 impl Day for Day8 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let (matrix, antennas) = input_to_antennas_and_matrix(&input);
-        let mut antinodes: HashSet<Position> = HashSet::new();
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        let (matrix, antennas) = input_to_antennas_and_matrix(&input)?;
+        let mut antinodes: FastSet<Position> = FastSet::default();
 
         for (_, antennas_of_type) in antennas.iter() {
-            for a in antennas_of_type.iter() {
-                for b in antennas_of_type.iter() {
-                    if a != b {
-                        let diff = a.diff(b);
-
-                        if let Some(first_antinode) = matrix.checked_position_apply(a, &diff) {
-                            antinodes.insert(first_antinode);
-                        }
-                        if let Some(second_antinode ) = matrix.checked_position_apply(b, &diff.reverse()) {
-                            antinodes.insert(second_antinode);
-                        }
-                    }
-                }
+            for (a, b) in antennas_of_type.iter().pairs() {
+                let (a, b) = (Point::from(a), Point::from(b));
+                // One antinode on each side, as far away from the antenna as the antennas are
+                // from each other
+                let candidates = [a + (a - b), b + (b - a)];
+                antinodes.extend(
+                    candidates
+                        .into_iter()
+                        .filter_map(Point::to_position)
+                        .filter(|pos| matrix.validate_position(pos)),
+                );
             }
         }
 
-        Some(antinodes.len() as DayResult)
+        Ok(antinodes.len() as DayResult)
     }
 
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let (matrix, antennas) = input_to_antennas_and_matrix(&input);
-        let mut antinodes: HashSet<Position> = HashSet::new();
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        let (matrix, antennas) = input_to_antennas_and_matrix(&input)?;
+        let mut antinodes: FastSet<Position> = FastSet::default();
 
         for (_, antennas_of_type) in antennas.iter() {
-            for a in antennas_of_type.iter() {
-                for b in antennas_of_type.iter() {
-                    if a != b {
-                        let diff = a.diff(b);
-                        // Note that we reversed the Antenna starting points, as this will include
-                        // the antennas itself as antinodes. We could do so separately, but then
-                        // we'd need to check whether it's the only antenna of the type and this is
-                        // a simple solution.
-                        iter_while_valid_position(&matrix, &mut antinodes, b, &diff);
-                        iter_while_valid_position(&matrix, &mut antinodes, a, &diff.reverse());
-                    }
-                }
+            for (a, b) in antennas_of_type.iter().pairs() {
+                let diff = a.diff(b);
+                // Note that we reversed the Antenna starting points, as this will include the
+                // antennas itself as antinodes. We could do so separately, but then we'd need to
+                // check whether it's the only antenna of the type and this is a simple solution.
+                antinodes.extend(matrix.ray(b, diff).map(|(pos, _)| pos));
+                antinodes.extend(matrix.ray(a, diff.reverse()).map(|(pos, _)| pos));
             }
         }
 
-        /*for node in antinodes.iter() {
-            if let Some(mut pos) = matrix.get_position_mut(node) {
-                *pos = '#';
+        if log_enabled!(Level::Trace) {
+            let mut matrix = matrix.clone();
+            for node in antinodes.iter() {
+                if let Some(pos) = matrix.get_position_mut(node) {
+                    *pos = '#';
+                }
             }
+            trace!("{}", matrix);
         }
-        println!("{}", matrix);*/
 
-        Some(antinodes.len() as DayResult)
+        Ok(antinodes.len() as DayResult)
 
     }
 
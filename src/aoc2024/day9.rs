@@ -1,238 +1,285 @@
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+use log::trace;
 use crate::day::{Day, DayResult, ExpectedResults, YearDay};
+use crate::error::Error;
 use crate::ExpectedResult;
+use crate::visualize::{frame_stride, Cell, Color, Frame, FrameSink, Visualize};
 
 pub(crate) struct Day9;
 
-type FsBlock = Option<u64>;
+type FileId = usize;
 
-type Fs = Box<Vec<FsBlock>>;
+/// A run of `len` blocks, starting at block `start`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Extent {
+    start: usize,
+    len: usize,
+}
 
-#[derive(Copy, Clone)]
-struct Fs2Block {
-    id: Option<u64>, // None if free space
-    size: u64,
+impl Extent {
+    fn end(&self) -> usize {
+        self.start + self.len
+    }
 }
 
-impl Fs2Block {
-    fn is_free_space(&self) -> bool { self.id.is_none() }
+/// The free extents, with one min-heap of starts per extent length. The leftmost extent of at
+/// least some length is on top of one of the heaps for that length or more.
+#[derive(Debug, Clone, Default)]
+struct FreeSpace {
+    by_len: Vec<BinaryHeap<Reverse<usize>>>,
 }
 
-type Fs2 = Box<VecDeque<Fs2Block>>;
-
-fn preprocess_input2(input: &String) -> Fs2 {
-    let mut fs: Fs2 = Box::new(VecDeque::new());
-    let numbers: Vec<u32> = input.chars().map(|c| c.to_digit(10)).collect::<Option<Vec<u32>>>().unwrap();
-    let mut is_file = true;
-    let mut file_number = 0u64;
-
-    for number in numbers {
-        let id = if is_file {
-            let rv = Some(file_number);
-            file_number += 1;
-            is_file = false;
-            rv
-        } else {
-            is_file = true;
-            None
-        };
+impl FreeSpace {
+    fn insert(&mut self, extent: Extent) {
+        if extent.len == 0 {
+            return;
+        }
+        if self.by_len.len() <= extent.len {
+            self.by_len.resize_with(extent.len + 1, BinaryHeap::new);
+        }
+        self.by_len[extent.len].push(Reverse(extent.start));
+    }
 
-        fs.push_back(Fs2Block { id, size: number as u64 });
+    /// Removes and returns the leftmost free extent of at least `min_len` blocks that starts
+    /// before block `before`.
+    fn take_leftmost(&mut self, min_len: usize, before: usize) -> Option<Extent> {
+        let (len, start) = (min_len.max(1)..self.by_len.len())
+            .filter_map(|len| self.by_len[len].peek().map(|Reverse(start)| (len, *start)))
+            .filter(|(_, start)| *start < before)
+            .min_by_key(|(_, start)| *start)?;
+        self.by_len[len].pop();
+        Some(Extent { start, len })
     }
+}
 
-    fs
+/// How [`Disk::compact`] moves files to the free space on their left.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Strategy {
+    /// Moves single blocks from the end of the disk into the leftmost gaps, splitting files.
+    Blocks,
+    /// Moves each file as a whole into the leftmost gap that fits it, if there is one.
+    WholeFiles,
 }
 
-fn preprocess_input(input: &String) -> Fs {
-    let mut fs: Fs = Box::new(Vec::new());
-    let numbers: Vec<u32> = input.chars().map(|c| c.to_digit(10)).collect::<Option<Vec<u32>>>().unwrap();
-    let mut is_file = true;
-    let mut file_number = 0u64;
+/// The files on a disk and an index of its free space.
+///
+/// Files only ever move left, so space that they free is never needed again and is not merged
+/// with the free space around it.
+#[derive(Debug, Clone)]
+struct Disk {
+    /// The extents of each file by id, left to right. Files have more than one extent after
+    /// block-wise compaction, and none if they are empty.
+    files: Vec<Vec<Extent>>,
+    free: FreeSpace,
+    size: usize,
+}
 
-    for number in numbers {
-        if is_file {
-            for _ in 0..number {
-                fs.push(Some(file_number));
-            }
-            file_number += 1;
+impl Disk {
+    /// Reads a disk map, where the digits alternate between the length of a file and the length
+    /// of the free space after it.
+    fn from_disk_map(input: &str) -> Result<Disk, Error> {
+        let mut disk = Disk {
+            files: Vec::new(),
+            free: FreeSpace::default(),
+            size: 0,
+        };
 
-            is_file = false;
-        } else {
-            for _ in 0..number {
-                fs.push(None);
+        for (idx, len) in parse_disk_map(input)?.into_iter().enumerate() {
+            let extent = Extent { start: disk.size, len: len as usize };
+            if idx % 2 == 0 {
+                disk.files.push(if extent.len > 0 { vec![extent] } else { Vec::new() });
+            } else {
+                disk.free.insert(extent);
             }
-            is_file = true;
+            disk.size = extent.end();
         }
-    }
 
-    fs
-}
-
-fn insert_fs_block_in_free_space(l: &mut Fs2, idx: usize, block: Fs2Block) {
-    let mut tail = l.split_off(idx);
-    let mut free_space = tail.pop_front().unwrap();
+        Ok(disk)
+    }
 
-    assert!(free_space.is_free_space(), "Trying to replace a non-free space");
-    assert!(free_space.size >= block.size, "Trying to replace free space with unfitting block");
+    /// Moves files left, processing them from the last one to the first, and calls `on_move`
+    /// after every move.
+    fn compact<F>(&mut self, strategy: Strategy, mut on_move: F) -> Result<(), Error>
+    where
+        F: FnMut(&Disk) -> Result<(), Error>,
+    {
+        for id in (0..self.files.len()).rev() {
+            let moved = match strategy {
+                Strategy::Blocks => self.move_blocks(id, &mut on_move)?,
+                Strategy::WholeFiles => self.move_whole_file(id, &mut on_move)?,
+            };
+            // Block-wise, a file that did not move completely means the gaps are filled
+            if strategy == Strategy::Blocks && !moved {
+                break;
+            }
+        }
 
-    free_space.size -= block.size;
-    l.push_back(block);
-    if free_space.size > 0 {
-        l.push_back(free_space);
+        Ok(())
     }
 
-    l.append(&mut tail);
-}
+    /// Fills the leftmost gaps with the blocks of a file, from its end. Returns whether all of
+    /// its blocks ended up left of the remaining free space.
+    fn move_blocks<F>(&mut self, id: FileId, on_move: &mut F) -> Result<bool, Error>
+    where
+        F: FnMut(&Disk) -> Result<(), Error>,
+    {
+        let mut remaining = std::mem::take(&mut self.files[id]);
+        let mut placed: Vec<Extent> = Vec::new();
+
+        while let Some(last) = remaining.last_mut() {
+            let Some(gap) = self.free.take_leftmost(1, last.start) else {
+                break;
+            };
+
+            let len = gap.len.min(last.len);
+            last.len -= len;
+            self.free.insert(Extent { start: last.end(), len });
+            self.free.insert(Extent { start: gap.start + len, len: gap.len - len });
+            placed.push(Extent { start: gap.start, len });
+            if last.len == 0 {
+                remaining.pop();
+            }
 
-fn remove_fs_block(l: &mut Fs2, idx: usize, mut size_of_block: u64) {
-    assert!(idx > 0);
-    let mut tail = l.split_off(idx - 1);
+            self.files[id] = placed.iter().chain(remaining.iter()).copied().collect();
+            on_move(self)?;
+        }
 
-    let prev = tail.pop_front().unwrap();
-    if prev.is_free_space() {
-        size_of_block += prev.size;
-    } else {
-        l.push_back(prev);
+        let done = remaining.is_empty();
+        placed.append(&mut remaining);
+        self.files[id] = placed;
+        Ok(done)
     }
 
-    // Remove the element we're replacing
-    tail.pop_front();
+    /// Moves a file as a whole into the leftmost gap that fits it. Files that were split are left
+    /// where they are. Returns whether the file moved.
+    fn move_whole_file<F>(&mut self, id: FileId, on_move: &mut F) -> Result<bool, Error>
+    where
+        F: FnMut(&Disk) -> Result<(), Error>,
+    {
+        let [file] = self.files[id][..] else {
+            return Ok(false);
+        };
+        let Some(gap) = self.free.take_leftmost(file.len, file.start) else {
+            return Ok(false);
+        };
 
-    if let Some(following) = tail.get(0) {
-        if following.is_free_space() {
-            size_of_block += following.size;
-            tail.pop_front();
-        }
+        self.files[id] = vec![Extent { start: gap.start, len: file.len }];
+        self.free.insert(Extent { start: gap.start + file.len, len: gap.len - file.len });
+        self.free.insert(file);
+        on_move(self)?;
+        Ok(true)
     }
 
-    l.push_back(Fs2Block {
-        id: None,
-        size: size_of_block,
-    });
-
-    l.append(&mut tail);
-}
+    /// The sum of the block positions multiplied by the id of the file in them.
+    fn checksum(&self) -> DayResult {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(id, extents)| extents.iter().map(move |extent| (id, extent)))
+            .map(|(id, extent)| {
+                // start + (start + 1) + ... + (start + len - 1)
+                let positions = extent.start * extent.len + extent.len * extent.len.saturating_sub(1) / 2;
+                id as DayResult * positions as DayResult
+            })
+            .sum()
+    }
 
-fn checksum_fs(fs: &Fs) -> DayResult {
-    let mut result = 0;
-    for (idx, block) in fs.iter().enumerate() {
-        if let Some(file_id) = block {
-            result += (idx as DayResult) * (*file_id as DayResult);
+    /// The file id in each block, `None` for free blocks.
+    fn blocks(&self) -> Vec<Option<FileId>> {
+        let mut blocks = vec![None; self.size];
+        for (id, extents) in self.files.iter().enumerate() {
+            for extent in extents {
+                blocks[extent.start..extent.end()].fill(Some(id));
+            }
         }
+        blocks
     }
-    result
-}
 
-fn checksum_fs2(fs: &Fs2) -> DayResult {
-    let mut result = 0;
-    let mut index = 0;
-
-    for block in fs.iter() {
-        if block.is_free_space() {
-            index += block.size;
-        } else {
-            for _ in 1..(block.size + 1) {
-                result += index * block.id.unwrap();
-                index += 1;
+    /// Lays the disk out as a roughly square grid, one cell per block, coloured by file id.
+    fn frame(&self, width: usize) -> Frame {
+        let mut frame = Frame::new(width, self.size.div_ceil(width), Cell::EMPTY);
+        for (idx, block) in self.blocks().into_iter().enumerate() {
+            if let Some(id) = block {
+                frame.set(idx % width, idx / width, Cell::new('█', Color::for_id(id as u64)));
             }
         }
+        frame
     }
-
-    result as DayResult
 }
 
-#[allow(dead_code)]
-fn display_fs(fs: &Fs) {
-    for item in fs.iter() {
-        match item {
-            None => print!("."),
-            Some(block) => print!("{block}")
+/// The blocks like in the puzzle description, `.` for free ones and the file id for the others.
+impl Display for Disk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for block in self.blocks() {
+            match block {
+                Some(id) => write!(f, "{id}")?,
+                None => write!(f, ".")?,
+            }
         }
+        Ok(())
     }
-    println!();
 }
 
-#[allow(dead_code)]
-fn display_fs2(fs: &Fs2) {
-    for block in fs.iter() {
-        for _ in 0..block.size {
-            if block.is_free_space() {
-                print!(".");
-            } else {
-                print!("{}", block.id.unwrap());
-            }
-        }
+fn parse_disk_map(input: &str) -> Result<Vec<u32>, Error> {
+    if input.trim_end().is_empty() {
+        return Err(Error::parse("the disk map is empty"));
     }
 
-    println!();
+    input
+        .trim_end()
+        .char_indices()
+        .map(|(idx, c)| {
+            c.to_digit(10)
+                .ok_or_else(|| Error::parse_at(input, &input[idx..], format!("invalid digit '{c}'")))
+        })
+        .collect()
 }
 
-impl Day for Day9 {
-    fn part1(&self, input: String) -> Option<DayResult> {
-        let fs = preprocess_input(&input);
-        let mut result = fs.clone();
-        let mut iter = fs.iter().enumerate();
-        let mut tail = iter.next_back().unwrap();
-
-        'outer: while let Some((idx, block)) = iter.next() {
-            if block.is_some() {
-                continue;
-            }
+/// Compacts the disk, logging it after every move.
+fn compact(input: &str, strategy: Strategy) -> Result<Disk, Error> {
+    let mut disk = Disk::from_disk_map(input)?;
+    trace!("{disk}");
 
-            // we found a free space, we need to swap it with an end
-            while tail.1.is_none() {
-                if let Some(next) = iter.next_back() {
-                    tail = next
-                } else {
-                    // we've reached the end
-                    break 'outer;
-                }
-            }
+    disk.compact(strategy, |disk| {
+        trace!("{disk}");
+        Ok(())
+    })?;
 
-            result.swap(idx, tail.0);
-            //display_fs(&result);
+    Ok(disk)
+}
 
-            if let Some(next) = iter.next_back() {
-                tail = next;
-            } else {
-                break;
+impl Visualize for Day9 {
+    fn visualize(&self, input: &str, sink: &mut dyn FrameSink) -> Result<(), Error> {
+        let mut disk = Disk::from_disk_map(input)?;
+        let width = disk.size.isqrt().max(1);
+        let stride = frame_stride(disk.files.len(), 200);
+        let mut moves = 0;
+
+        sink.frame(&disk.frame(width))?;
+        disk.compact(Strategy::WholeFiles, |disk| {
+            moves += 1;
+            if moves % stride == 0 {
+                sink.frame(&disk.frame(width))?;
             }
-        }
+            Ok(())
+        })?;
+        sink.frame(&disk.frame(width))
+    }
+}
 
-        Some(checksum_fs(&result))
-    }
-
-    fn part2(&self, input: String) -> Option<DayResult> {
-        let mut fs = preprocess_input2(&input);
-        let mut index = fs.len() - 1;
-        //display_fs2(&fs);
-
-        while index > 0 {
-            let next = *fs.get(index).unwrap();
-            if !next.is_free_space() {
-                let mut index_back = 0;
-
-                while index > index_back {
-                    let block = fs.get(index_back).unwrap();
-                    if block.is_free_space() {
-                        let block = *block;
-                        if block.size >= next.size {
-                            remove_fs_block(&mut fs, index, next.size);
-                            insert_fs_block_in_free_space(&mut fs, index_back, next);
-                            //display_fs2(&fs);
-                            break;
-                        }
-                    }
-
-                    index_back += 1;
-                }
-            }
+impl Day for Day9 {
+    fn part1(&self, input: String) -> Result<DayResult, Error> {
+        Ok(compact(&input, Strategy::Blocks)?.checksum())
+    }
 
-            index -= 1;
-        }
+    fn part2(&self, input: String) -> Result<DayResult, Error> {
+        Ok(compact(&input, Strategy::WholeFiles)?.checksum())
+    }
 
-        //unimplemented!();
-        Some(checksum_fs2(&fs))
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        Some(self)
     }
 
     fn get_expected_results(&self) -> ExpectedResults {
@@ -247,7 +294,31 @@ impl Day for Day9 {
         String::from("Checksum of defragmented filesystem")
     }
 
-    /*fn part2_result_description(&self) -> String {
-        String::from("")
-    }*/
-}
\ No newline at end of file
+    fn part2_result_description(&self) -> String {
+        String::from("Checksum of filesystem defragmented by whole files")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compact() {
+        let input = "2333133121414131402";
+        let disk = Disk::from_disk_map(input).unwrap();
+        assert_eq!(disk.to_string(), "00...111...2...333.44.5555.6666.777.888899");
+
+        let blocks = compact(input, Strategy::Blocks).unwrap();
+        assert_eq!(blocks.to_string(), "0099811188827773336446555566..............");
+        assert_eq!(blocks.checksum(), 1928);
+
+        let whole_files = compact(input, Strategy::WholeFiles).unwrap();
+        assert_eq!(whole_files.to_string(), "00992111777.44.333....5555.6666.....8888..");
+        assert_eq!(whole_files.checksum(), 2858);
+
+        // Empty files and a disk that is already compact
+        assert_eq!(compact("1021003", Strategy::Blocks).unwrap().to_string(), "011333.");
+        assert_eq!(compact("103", Strategy::WholeFiles).unwrap().checksum(), 1 + 2 + 3);
+    }
+}
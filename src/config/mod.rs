@@ -0,0 +1,273 @@
+//! Runner defaults from `aoc.toml`.
+//!
+//! The file is looked up in the crate root first and in `$XDG_CONFIG_HOME/aoc/` (or
+//! `~/.config/aoc/`) second. Every key mirrors a command line flag:
+//!
+//! ```toml
+//! year = [2024]          # --year
+//! day = [1, 2]           # --day
+//! latest = false         # --latest
+//! verbosity = 1          # -v / -q
+//! visualize = "gif"      # --visualize
+//! visualize_test = true  # --visualize-test
+//! repeat = 21            # --repeat
+//! ```
+//!
+//! The boolean flags have a `--no-` counterpart to switch them off again, e.g. `--no-latest`.
+//! `visualize = false` and `--no-visualize` disable the visualisation.
+
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{Error, ParseError};
+use crate::visualize::Output;
+
+mod toml;
+
+use toml::{Entry, Value};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+/// Runner settings. Unset fields fall back to the defaults of the accessors.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Config {
+    pub years: Option<Vec<u16>>,
+    pub days: Option<Vec<u16>>,
+    pub latest: Option<bool>,
+    pub verbosity: Option<i8>,
+    /// `Some(None)` if the visualisation was switched off explicitly.
+    pub visualize: Option<Option<Output>>,
+    pub visualize_test: Option<bool>,
+    pub repeat: Option<u16>,
+    source: Option<PathBuf>,
+}
+
+impl Config {
+    /// Loads the first configuration file found, or an empty configuration if there is none.
+    pub fn load() -> Result<Self, Error> {
+        for path in Self::search_paths() {
+            if path.is_file() {
+                return Self::from_file(&path);
+            }
+        }
+
+        Ok(Config::default())
+    }
+
+    pub fn search_paths() -> Vec<PathBuf> {
+        let mut paths = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join(FILE_NAME)];
+
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+        if let Some(config_home) = config_home {
+            paths.push(config_home.join("aoc").join(FILE_NAME));
+        }
+
+        paths
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(path)?;
+        let mut config = Self::from_toml(&content).map_err(|error| Error::Config(path.to_path_buf(), error))?;
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    pub fn from_toml(input: &str) -> Result<Self, ParseError> {
+        let mut config = Config::default();
+
+        for entry in toml::parse(input)? {
+            match entry.key.as_str() {
+                "year" => config.years = Some(number_list(&entry)?),
+                "day" => config.days = Some(number_list(&entry)?),
+                "latest" => config.latest = Some(boolean(&entry)?),
+                "verbosity" => config.verbosity = Some(number(&entry, &entry.value)?),
+                "visualize" if entry.value == Value::Boolean(false) => config.visualize = Some(None),
+                "visualize" => {
+                    let output = string(&entry)?
+                        .parse()
+                        .map_err(|error| match error {
+                            Error::Parse(error) => invalid(&entry, error.message),
+                            other => invalid(&entry, other.to_string()),
+                        })?;
+                    config.visualize = Some(Some(output));
+                }
+                "visualize_test" => config.visualize_test = Some(boolean(&entry)?),
+                "repeat" => match number(&entry, &entry.value)? {
+                    0 => return Err(invalid(&entry, String::from("expected at least one run"))),
+                    runs => config.repeat = Some(runs),
+                },
+                key => return Err(invalid(&entry, format!("unknown key '{key}'"))),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Returns this configuration with every setting of `other` taking precedence.
+    pub fn overridden_by(self, other: Config) -> Config {
+        Config {
+            years: other.years.or(self.years),
+            days: other.days.or(self.days),
+            latest: other.latest.or(self.latest),
+            verbosity: other.verbosity.or(self.verbosity),
+            visualize: other.visualize.or(self.visualize),
+            visualize_test: other.visualize_test.or(self.visualize_test),
+            repeat: other.repeat.or(self.repeat),
+            source: other.source.or(self.source),
+        }
+    }
+
+    /// The file this configuration was read from, if any.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    pub fn latest(&self) -> bool {
+        self.latest.unwrap_or(false)
+    }
+
+    pub fn verbosity(&self) -> i8 {
+        self.verbosity.unwrap_or(0)
+    }
+
+    pub fn visualize(&self) -> Option<&Output> {
+        self.visualize.as_ref()?.as_ref()
+    }
+
+    pub fn visualize_test(&self) -> bool {
+        self.visualize_test.unwrap_or(false)
+    }
+
+    /// How often the real input of each day is solved again to report the median time. The
+    /// default of one run skips this.
+    pub fn repeat(&self) -> u16 {
+        self.repeat.unwrap_or(1)
+    }
+}
+
+impl Display for Config {
+    /// Writes the effective settings in the format of the configuration file.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(path) => writeln!(f, "# loaded from {}", path.display())?,
+            None => writeln!(f, "# no configuration file found")?,
+        }
+
+        let list = |values: &Option<Vec<u16>>| {
+            values.as_ref().map(|values| Value::Array(values.iter().map(|v| Value::Integer(*v as i64)).collect()))
+        };
+        match list(&self.years) {
+            Some(years) => writeln!(f, "year = {years}")?,
+            None => writeln!(f, "# year = [] (all years)")?,
+        }
+        match list(&self.days) {
+            Some(days) => writeln!(f, "day = {days}")?,
+            None => writeln!(f, "# day = [] (all days)")?,
+        }
+        writeln!(f, "latest = {}", self.latest())?;
+        writeln!(f, "verbosity = {}", self.verbosity())?;
+        match self.visualize() {
+            Some(output) => writeln!(f, "visualize = {}", Value::String(output.to_string()))?,
+            None => writeln!(f, "# visualize = \"term\" (disabled)")?,
+        }
+        writeln!(f, "visualize_test = {}", self.visualize_test())?;
+        write!(f, "repeat = {}", self.repeat())
+    }
+}
+
+fn invalid(entry: &Entry, message: String) -> ParseError {
+    ParseError::new(format!("{message} for '{}'", entry.key)).at(Some(entry.location))
+}
+
+fn expected(entry: &Entry, expected: &str, found: &Value) -> ParseError {
+    invalid(entry, format!("expected {expected}, found {}", found.type_name()))
+}
+
+fn number<T: TryFrom<i64>>(entry: &Entry, value: &Value) -> Result<T, ParseError> {
+    match value {
+        Value::Integer(i) => T::try_from(*i).map_err(|_| invalid(entry, format!("{i} is out of range"))),
+        other => Err(expected(entry, "an integer", other)),
+    }
+}
+
+/// Accepts a single number as well as a list of numbers.
+fn number_list(entry: &Entry) -> Result<Vec<u16>, ParseError> {
+    match &entry.value {
+        Value::Array(values) => values.iter().map(|value| number(entry, value)).collect(),
+        value => Ok(vec![number(entry, value)?]),
+    }
+}
+
+fn boolean(entry: &Entry) -> Result<bool, ParseError> {
+    match &entry.value {
+        Value::Boolean(b) => Ok(*b),
+        other => Err(expected(entry, "a boolean", other)),
+    }
+}
+
+fn string(entry: &Entry) -> Result<&str, ParseError> {
+    match &entry.value {
+        Value::String(s) => Ok(s),
+        other => Err(expected(entry, "a string", other)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Location;
+
+    #[test]
+    fn test_from_toml() {
+        let file = Config::from_toml("year = 2024\nday = [1, 2]\nverbosity = -1\nvisualize = 'png:out'\n").unwrap();
+        assert_eq!(file.years, Some(vec![2024]));
+        assert_eq!(file.days, Some(vec![1, 2]));
+        assert_eq!(file.visualize(), Some(&Output::Png(PathBuf::from("out"))));
+
+        let cli = Config {
+            days: Some(vec![5]),
+            latest: Some(true),
+            ..Config::default()
+        };
+        let effective = file.overridden_by(cli);
+        assert_eq!(effective.years, Some(vec![2024]));
+        assert_eq!(effective.days, Some(vec![5]));
+        assert!(effective.latest());
+        assert_eq!(effective.verbosity(), -1);
+        assert!(!effective.visualize_test());
+        assert_eq!(Config::from_toml(&effective.to_string()).unwrap().to_string(), effective.to_string());
+
+        let error = Config::from_toml("latest = true\nyears = [2024]").unwrap_err();
+        assert_eq!(error.location, Some(Location::new(2, 1)));
+        assert!(Config::from_toml("day = [1, 300000]").is_err());
+        assert!(Config::from_toml("latest = 1").is_err());
+        assert!(Config::from_toml("visualize = true").is_err());
+        assert_eq!(Config::from_toml("repeat = 21").unwrap().repeat(), 21);
+        assert!(Config::from_toml("repeat = 0").is_err());
+    }
+
+    #[test]
+    fn test_switched_off() {
+        let file = Config::from_toml("latest = true\nvisualize = 'gif'\nvisualize_test = true").unwrap();
+        let cli = Config {
+            latest: Some(false),
+            visualize: Some(None),
+            visualize_test: Some(false),
+            ..Config::default()
+        };
+        let effective = file.clone().overridden_by(cli);
+        assert!(!effective.latest());
+        assert_eq!(effective.visualize(), None);
+        assert!(!effective.visualize_test());
+
+        // Nothing given on the command line keeps the file settings
+        let effective = file.overridden_by(Config::default());
+        assert!(effective.latest() && effective.visualize_test());
+        assert_eq!(effective.visualize(), Some(&Output::Gif(PathBuf::from("./visualizations"))));
+        assert_eq!(Config::from_toml("visualize = false").unwrap().visualize, Some(None));
+    }
+}
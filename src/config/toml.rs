@@ -0,0 +1,289 @@
+//! Just enough TOML for the runner configuration: tables, (dotted) bare keys, strings, integers,
+//! booleans and arrays. Dates, floats, inline tables and multi-line strings are not supported.
+
+use std::fmt::{Display, Formatter};
+use crate::error::{Location, ParseError};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Integer(_) => "integer",
+            Value::Boolean(_) => "boolean",
+            Value::Array(_) => "array",
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{s:?}"),
+            Value::Integer(i) => write!(f, "{i}"),
+            Value::Boolean(b) => write!(f, "{b}"),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A key/value pair, with the key including its table prefix (`table.key`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: Value,
+    pub location: Location,
+}
+
+/// Parses a document into its entries, in the order they appear.
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let mut parser = Parser { input, pos: 0 };
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut table = String::new();
+
+    loop {
+        parser.skip_whitespace_and_comments(true);
+        let Some(c) = parser.peek() else {
+            return Ok(entries);
+        };
+        let location = parser.location();
+
+        if c == '[' {
+            parser.pos += 1;
+            parser.skip_whitespace_and_comments(false);
+            table = parser.key()?;
+            parser.skip_whitespace_and_comments(false);
+            parser.expect(']')?;
+        } else {
+            let mut key = parser.key()?;
+            if !table.is_empty() {
+                key = format!("{table}.{key}");
+            }
+            parser.skip_whitespace_and_comments(false);
+            parser.expect('=')?;
+            parser.skip_whitespace_and_comments(false);
+            let value = parser.value()?;
+
+            if entries.iter().any(|entry| entry.key == key) {
+                return Err(ParseError::new(format!("duplicate key '{key}'")).at(Some(location)));
+            }
+            entries.push(Entry { key, value, location });
+        }
+
+        parser.skip_whitespace_and_comments(false);
+        match parser.peek() {
+            None | Some('\n') => {}
+            Some(c) => return Err(parser.error(format!("expected end of line, found '{c}'"))),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn location(&self) -> Location {
+        Location::of(self.input, &self.input[self.pos..]).unwrap_or(Location::new(1, 1))
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(message).at(Some(self.location()))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected '{expected}', found '{c}'"))),
+            None => Err(self.error(format!("expected '{expected}', found the end of the file"))),
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\r' => self.pos += 1,
+                '\n' if newlines => self.pos += 1,
+                '#' => {
+                    let rest = &self.input[self.pos..];
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn bare_key(&mut self) -> Result<&str, ParseError> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a key"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// A bare key, possibly dotted.
+    fn key(&mut self) -> Result<String, ParseError> {
+        let mut key = self.bare_key()?.to_string();
+        loop {
+            self.skip_whitespace_and_comments(false);
+            if self.peek() != Some('.') {
+                return Ok(key);
+            }
+            self.pos += 1;
+            self.skip_whitespace_and_comments(false);
+            key.push('.');
+            key.push_str(self.bare_key()?);
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            Some('[') => self.array(),
+            Some('t' | 'f') => {
+                let location = self.location();
+                match self.bare_key()? {
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    other => Err(ParseError::new(format!("invalid value '{other}'")).at(Some(location))),
+                }
+            }
+            Some('+' | '-' | '0'..='9') => self.integer(),
+            Some(c) => Err(self.error(format!("invalid value starting with '{c}'"))),
+            None => Err(self.error("expected a value, found the end of the file")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<Value, ParseError> {
+        let location = self.location();
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .char_indices()
+            .find(|(idx, c)| !(c.is_ascii_digit() || *c == '_' || (*idx == 0 && (*c == '+' || *c == '-'))))
+            .map_or(rest.len(), |(idx, _)| idx);
+        let token = &rest[..len];
+        self.pos += len;
+
+        token
+            .replace('_', "")
+            .parse()
+            .map(Value::Integer)
+            .map_err(|e| ParseError::new(format!("invalid integer '{token}'")).at(Some(location)).caused_by(e))
+    }
+
+    fn basic_string(&mut self) -> Result<Value, ParseError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(Value::String(value)),
+                Some('\\') => {
+                    let escaped = match self.next() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some(c) => return Err(self.error(format!("unsupported escape sequence '\\{c}'"))),
+                        None => return Err(self.error("unterminated string")),
+                    };
+                    value.push(escaped);
+                }
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<Value, ParseError> {
+        self.expect('\'')?;
+        let rest = &self.input[self.pos..];
+        match rest.find(['\'', '\n']) {
+            Some(end) if rest[end..].starts_with('\'') => {
+                self.pos += end + 1;
+                Ok(Value::String(rest[..end].to_string()))
+            }
+            _ => Err(self.error("unterminated string")),
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments(true);
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(values));
+            }
+
+            values.push(self.value()?);
+            self.skip_whitespace_and_comments(true);
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']' in array")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = "# runner defaults\nyear = [2023, 2024] # trailing comment\n\n[visualize]\noutput = \"gif:/tmp/a \\\"b\\\"\"\ntest = true\nlimit = -1_000\nnested = [\n  ['x'],\n  [],\n]\n";
+        let entries = parse(input).unwrap();
+        let get = |key: &str| entries.iter().find(|entry| entry.key == key).unwrap();
+
+        assert_eq!(get("year").value, Value::Array(vec![Value::Integer(2023), Value::Integer(2024)]));
+        assert_eq!(get("visualize.output").value, Value::String(String::from("gif:/tmp/a \"b\"")));
+        assert_eq!(get("visualize.output").location, Location::new(5, 1));
+        assert_eq!(get("visualize.test").value, Value::Boolean(true));
+        assert_eq!(get("visualize.limit").value, Value::Integer(-1000));
+        assert_eq!(get("visualize.nested").value.to_string(), "[[\"x\"], []]");
+
+        let error = parse("year = 2024\nday = [1, 2\nlatest = true").unwrap_err();
+        assert_eq!(error.location, Some(Location::new(3, 1)));
+        assert_eq!(parse("a = 1\na = 2").unwrap_err().location, Some(Location::new(2, 1)));
+        assert!(parse("a = 1 b = 2").is_err());
+        assert!(parse("a = \"open").is_err());
+    }
+}
@@ -1,33 +1,101 @@
-use std::cmp::{min, Ordering};
+use std::cell::RefCell;
+use std::cmp::min;
 use std::fmt::Display;
 use std::fs;
-use std::time::Instant;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+use crate::error::Error;
+use crate::tools::BigInt;
+use crate::visualize::Visualize;
 
 pub type BoxedDay = Box<dyn Day>;
 
+thread_local! {
+    static STATS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds a line of statistics, e.g. how well a cache worked, to the run output of the part that
+/// is being solved.
+pub fn report_stats(stats: impl Display) {
+    STATS.with_borrow_mut(|lines| lines.push(stats.to_string()));
+}
+
+fn take_stats() -> Vec<String> {
+    STATS.with_borrow_mut(std::mem::take)
+}
+
+/// The middle of the given times, or the mean of the two middle ones for an even count.
+fn median(mut times: Vec<Duration>) -> Option<Duration> {
+    times.sort();
+    let middle = times.len() / 2;
+    match times.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(times[middle]),
+        _ => Some((times[middle - 1] + times[middle]) / 2),
+    }
+}
+
 pub type DayResult = i128;
 
-#[derive(Copy, Clone, Eq, PartialEq, PartialOrd)]
+/// The answer to a single part of a day: a [`DayResult`], or a [`BigInt`] for answers that
+/// don't fit into one. Always uses `Number` if possible, so equal answers compare equal.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Answer {
+    Number(DayResult),
+    Big(BigInt),
+}
+
+impl From<DayResult> for Answer {
+    fn from(value: DayResult) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match DayResult::try_from(&value) {
+            Ok(number) => Answer::Number(number),
+            Err(_) => Answer::Big(value),
+        }
+    }
+}
+
+impl PartialEq<DayResult> for Answer {
+    fn eq(&self, other: &DayResult) -> bool {
+        matches!(self, Answer::Number(number) if number == other)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Big(big) => write!(f, "{big}"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct YearDay {
     pub year: u16,
     pub day: u16,
 }
 
 pub struct ExpectedResults {
-    part1_test: Vec<DayResult>,
-    part1_real: Option<DayResult>,
-    part2_test: Option<Vec<DayResult>>,
-    part2_real: Option<DayResult>,
+    part1_test: Vec<Answer>,
+    part1_real: Option<Answer>,
+    part2_test: Option<Vec<Answer>>,
+    part2_real: Option<Answer>,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum RunType {
     Test,
     Actual,
 }
 
 #[repr(u16)]
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Part {
     Part1 = 1u16,
     Part2 = 2u16,
@@ -77,22 +145,48 @@ impl ExpectedResults {
         part1_real: Option<DayResult>,
         part2_test: Option<Vec<DayResult>>,
         part2_real: Option<DayResult>) -> ExpectedResults {
+        let answers = |results: Vec<DayResult>| results.into_iter().map(Answer::from).collect();
         ExpectedResults {
-            part1_test,
-            part1_real,
-            part2_test,
-            part2_real,
+            part1_test: answers(part1_test),
+            part1_real: part1_real.map(Answer::from),
+            part2_test: part2_test.map(answers),
+            part2_real: part2_real.map(Answer::from),
+        }
+    }
+
+    /// Replaces the expected real result of a part with one given as decimal digits, for answers
+    /// that don't fit into a [`DayResult`]. Panics if `digits` is not an integer.
+    pub fn with_big_real_result(mut self, part: Part, digits: &str) -> Self {
+        let answer = Some(Answer::from(digits.parse::<BigInt>().unwrap_or_else(|error| panic!("{error}"))));
+        match part {
+            Part::Part1 => self.part1_real = answer,
+            Part::Part2 => self.part2_real = answer,
+        }
+        self
+    }
+
+    /// Replaces the expected test results of a part with ones given as decimal digits, see
+    /// [`ExpectedResults::with_big_real_result`].
+    pub fn with_big_test_results(mut self, part: Part, digits: &[&str]) -> Self {
+        let answers = digits
+            .iter()
+            .map(|digits| Answer::from(digits.parse::<BigInt>().unwrap_or_else(|error| panic!("{error}"))))
+            .collect();
+        match part {
+            Part::Part1 => self.part1_test = answers,
+            Part::Part2 => self.part2_test = Some(answers),
         }
+        self
     }
 
-    pub fn get_expected_real_result(&self, part: &Part) -> Option<DayResult> {
+    pub fn get_expected_real_result(&self, part: &Part) -> Option<Answer> {
         match part {
-            Part::Part1 => self.part1_real,
-            Part::Part2 => self.part2_real,
+            Part::Part1 => self.part1_real.clone(),
+            Part::Part2 => self.part2_real.clone(),
         }
     }
 
-    pub fn get_expected_test_results(&self, part: &Part) -> Option<&Vec<DayResult>> {
+    pub fn get_expected_test_results(&self, part: &Part) -> Option<&Vec<Answer>> {
         match part {
             Part::Part1 => Some(&self.part1_test),
             Part::Part2 => self.part2_test.as_ref(),
@@ -106,18 +200,6 @@ impl YearDay {
     }
 }
 
-impl Ord for YearDay {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let tmp = self.year.cmp(&other.year);
-        if tmp.is_eq() {
-            self.day.cmp(&other.day)
-        }
-        else {
-            tmp
-        }
-    }
-}
-
 impl Display for RunType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let str = match self {
@@ -135,8 +217,8 @@ impl Display for Part {
 }
 
 pub trait Day {
-    fn part1(&self, input: String) -> Option<DayResult>;
-    fn part2(&self, input: String) -> Option<DayResult>;
+    fn part1(&self, input: String) -> Result<DayResult, Error>;
+    fn part2(&self, input: String) -> Result<DayResult, Error>;
     fn get_expected_results(&self) -> ExpectedResults;
     fn get_year_and_date(&self) -> YearDay;
 
@@ -148,6 +230,19 @@ pub trait Day {
         String::from("Result for part 2")
     }
 
+    /// Solves a part. Days whose answers may not fit into a [`DayResult`] override this to return
+    /// a [`BigInt`], and implement `part1`/`part2` by converting its result.
+    fn answer(&self, part: &Part, input: String) -> Result<Answer, Error> {
+        match part {
+            Part::Part1 => self.part1(input).map(Answer::from),
+            Part::Part2 => self.part2(input).map(Answer::from),
+        }
+    }
+
+    /// Days with a visualisation return themselves here.
+    fn visualizer(&self) -> Option<&dyn Visualize> {
+        None
+    }
 
     fn get_input(&self, run_type: &RunType, part: &Part) -> Option<String> {
         self.get_input_indexed(run_type, part, 1)
@@ -181,33 +276,54 @@ pub trait Day {
         None
     }
 
-    fn execute(&self, part: &Part, input: String, expected_result: Option<DayResult>) -> RunResultType {
+    fn execute(&self, part: &Part, input: String, expected_result: Option<Answer>) -> RunResultType {
         let result_description = match part {
             Part::Part1 => self.part1_result_description(),
             Part::Part2 => self.part2_result_description(),
         };
 
-        if let Some(result) = match part {
-            Part::Part1 => self.part1(input),
-            Part::Part2 => self.part2(input),
-        } {
-            if let Some(expected) = expected_result {
-                if result == expected {
-                    println!("{result_description}: {result} (verified)");
-                    RunResultType::Success
+        take_stats();
+        // Release builds check for overflows too, which panic instead of giving wrong answers
+        let result = match catch_unwind(AssertUnwindSafe(|| self.answer(part, input))) {
+            Ok(result) => result,
+            Err(panic) => {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or(panic.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("unknown cause");
+                println!("Failed: Day function for {part} panicked: {message}");
+                return RunResultType::Failed;
+            }
+        };
+
+        let result_type = match result {
+            Ok(result) => {
+                if let Some(expected) = expected_result {
+                    if result == expected {
+                        println!("{result_description}: {result} (verified)");
+                        RunResultType::Success
+                    } else {
+                        println!("{result_description}: {result}");
+                        println!(" => FAILED! {result} != {expected}");
+                        RunResultType::Failed
+                    }
                 } else {
-                    println!("{result_description}: {result}");
-                    println!(" => FAILED! {result} != {expected}");
-                    RunResultType::Failed
+                    println!("{}: {} (no reference value given)", result_description, result);
+                    RunResultType::Unverified
                 }
-            } else {
-                println!("{}: {} (no reference value given)", result_description, result);
-                RunResultType::Unverified
             }
-        } else {
-            println!("Failed: Day function for {part} did not return a valid result!");
-            RunResultType::Failed
+            Err(error) => {
+                println!("Failed: Day function for {part} returned an error: {error}");
+                print_error_sources(&error);
+                RunResultType::Failed
+            }
+        };
+
+        for stats in take_stats() {
+            println!("  {stats}");
         }
+        result_type
     }
 
     fn run_tests(&self, part: &Part) -> Vec<RunResultType> {
@@ -215,7 +331,7 @@ pub trait Day {
             assert!(!expected.is_empty());
             if expected.len() == 1 {
                 if let Some(input) = self.get_input(&RunType::Test, part) {
-                    vec!(self.execute(part, input, Some(*expected.first().unwrap())))
+                    vec!(self.execute(part, input, expected.first().cloned()))
                 } else {
                     println!("Part {part} failed - could not find input!");
                     vec!(RunResultType::Failed)
@@ -226,7 +342,7 @@ pub trait Day {
                     let idx = idx + 1;
                     if let Some(input) = self.get_input_indexed(&RunType::Test, part, idx) {
                         results.push(
-                            self.execute(part, input, Some(*expected))
+                            self.execute(part, input, Some(expected.clone()))
                         );
                     } else {
                         println!("Part {part} test #{idx} failed - could not find input!");
@@ -279,6 +395,28 @@ pub trait Day {
         result
     }
 
+    /// Solves both parts of the real input `runs` times without printing anything and returns the
+    /// median time of a run, or `None` if an input is missing. Only meant for days that already
+    /// ran successfully, as errors and panics are not reported.
+    fn median_time(&self, runs: u16) -> Option<Duration> {
+        let inputs = [Part::Part1, Part::Part2]
+            .map(|part| self.get_input(&RunType::Actual, &part).map(|input| (part, input)));
+        let inputs: Vec<(Part, String)> = inputs.into_iter().collect::<Option<_>>()?;
+
+        let times = (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                for (part, input) in inputs.iter() {
+                    let _ = self.answer(part, input.clone());
+                }
+                start.elapsed()
+            })
+            .collect();
+        take_stats();
+
+        median(times)
+    }
+
     fn run(&self) -> Vec<RunResultType> {
         let mut results = Vec::new();
 
@@ -288,4 +426,85 @@ pub trait Day {
 
         results
     }
-}
\ No newline at end of file
+}
+fn print_error_sources(error: &dyn std::error::Error) {
+    let mut source = error.source();
+    while let Some(cause) = source {
+        println!("  caused by: {cause}");
+        source = cause.source();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Part 1 is 2^n for the number n in the input, part 2 computes the same with a `DayResult`.
+    struct PowersOfTwo;
+
+    fn power_of_two(input: &str) -> Result<BigInt, Error> {
+        let exponent: u32 = input.trim().parse().map_err(|_| Error::parse("expected a number"))?;
+        Ok(BigInt::from(2).pow(exponent))
+    }
+
+    impl Day for PowersOfTwo {
+        fn part1(&self, input: String) -> Result<DayResult, Error> {
+            DayResult::try_from(&power_of_two(&input)?)
+        }
+
+        fn part2(&self, input: String) -> Result<DayResult, Error> {
+            let exponent: u32 = input.trim().parse().map_err(|_| Error::parse("expected a number"))?;
+            Ok((0..exponent).fold(1, |acc: DayResult, _| acc * 2))
+        }
+
+        fn answer(&self, part: &Part, input: String) -> Result<Answer, Error> {
+            match part {
+                Part::Part1 => Ok(power_of_two(&input)?.into()),
+                Part::Part2 => self.part2(input).map(Answer::from),
+            }
+        }
+
+        fn get_expected_results(&self) -> ExpectedResults {
+            ExpectedResult!(8, 1024).with_big_real_result(Part::Part1, "1361129467683753853853498429727072845824")
+        }
+
+        fn get_year_and_date(&self) -> YearDay {
+            YearDay::y2024(0)
+        }
+    }
+
+    #[test]
+    fn test_big_answers() {
+        let day = PowersOfTwo;
+        let expected = day.get_expected_results();
+        assert_eq!(expected.get_expected_test_results(&Part::Part1), Some(&vec![Answer::Number(8)]));
+        assert!(matches!(expected.get_expected_real_result(&Part::Part1), Some(Answer::Big(_))));
+
+        assert_eq!(day.answer(&Part::Part1, String::from("3")).unwrap(), 8);
+        assert_eq!(day.execute(&Part::Part1, String::from("10"), Some(Answer::Number(1024))), RunResultType::Success);
+        let big = String::from("130");
+        assert_eq!(day.execute(&Part::Part1, big.clone(), expected.get_expected_real_result(&Part::Part1)), RunResultType::Success);
+        assert!(matches!(day.part1(big), Err(Error::Solution(_))));
+
+        // 2^127 overflows an i128
+        assert_eq!(day.execute(&Part::Part2, String::from("126"), None), RunResultType::Unverified);
+        assert_eq!(day.execute(&Part::Part2, String::from("127"), None), RunResultType::Failed);
+    }
+
+    #[test]
+    fn test_report_stats() {
+        report_stats("1 hit");
+        report_stats(format_args!("{} misses", 2));
+        assert_eq!(take_stats(), vec!["1 hit", "2 misses"]);
+        assert!(take_stats().is_empty());
+    }
+
+    #[test]
+    fn test_median() {
+        let millis = |values: &[u64]| values.iter().map(|ms| Duration::from_millis(*ms)).collect::<Vec<_>>();
+        assert_eq!(median(millis(&[])), None);
+        assert_eq!(median(millis(&[7])), Some(Duration::from_millis(7)));
+        assert_eq!(median(millis(&[9, 1, 4])), Some(Duration::from_millis(4)));
+        assert_eq!(median(millis(&[9, 1, 4, 2])), Some(Duration::from_millis(3)));
+    }
+}
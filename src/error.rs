@@ -0,0 +1,214 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use crate::day::{RunType, YearDay};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+type BoxedSource = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A position in the puzzle input. Both line and column start at 1, like in any editor.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Location { line, column }
+    }
+
+    /// Computes the location of `part` in `input`. `part` must be a subslice of `input`, which is
+    /// what `lines`, `split` and friends hand out.
+    pub fn of(input: &str, part: &str) -> Option<Location> {
+        let offset = (part.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+        if offset + part.len() > input.len() || !input.is_char_boundary(offset) {
+            return None;
+        }
+
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        Some(Location { line, column })
+    }
+
+    /// Treats this location as relative to `base` and returns the absolute location.
+    pub fn offset(self, base: Location) -> Location {
+        if self.line == 1 {
+            Location::new(base.line, base.column + self.column - 1)
+        } else {
+            Location::new(base.line + self.line - 1, self.column)
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// The puzzle input did not have the expected format.
+#[derive(Debug)]
+pub struct ParseError {
+    pub location: Option<Location>,
+    pub message: String,
+    source: Option<BoxedSource>,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ParseError {
+            location: None,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn at(mut self, location: Option<Location>) -> Self {
+        self.location = location;
+        self
+    }
+
+    pub fn caused_by(mut self, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{} at {location}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.as_ref().map(|source| source.as_ref() as _)
+    }
+}
+
+/// Errors returned by the days and the library entry points.
+#[derive(Debug)]
+pub enum Error {
+    /// No solution is registered for the requested year and day.
+    UnknownDay(YearDay),
+    /// The day predates the `Day` trait and only prints its results.
+    Unsupported(YearDay),
+    /// There is no input file of the given kind for the day.
+    MissingInput(YearDay, RunType),
+    /// The input could not be parsed.
+    Parse(ParseError),
+    /// The input was parsed, but the puzzle could not be solved with it.
+    Solution(String),
+    /// Reading or writing a file failed.
+    Io(std::io::Error),
+    /// The configuration file at the given path is invalid.
+    Config(PathBuf, ParseError),
+}
+
+impl Error {
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse(ParseError::new(message))
+    }
+
+    pub fn parse_at(input: &str, part: &str, message: impl Into<String>) -> Self {
+        Error::Parse(ParseError::new(message).at(Location::of(input, part)))
+    }
+
+    pub fn solution(message: impl Into<String>) -> Self {
+        Error::Solution(message.into())
+    }
+
+    /// Moves the location of a parse error that was reported for `part` so it is relative to
+    /// `input` instead.
+    pub fn within(self, input: &str, part: &str) -> Self {
+        match self {
+            Error::Parse(mut error) => {
+                if let (Some(location), Some(base)) = (error.location, Location::of(input, part)) {
+                    error.location = Some(location.offset(base));
+                }
+                Error::Parse(error)
+            }
+            other => other,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnknownDay(YearDay { year, day }) => {
+                write!(f, "no solution registered for {year}, day {day}")
+            }
+            Error::Unsupported(YearDay { year, day }) => {
+                write!(f, "{year}, day {day} does not return its results")
+            }
+            Error::MissingInput(YearDay { year, day }, run_type) => {
+                write!(f, "could not find {run_type} input for {year}, day {day}")
+            }
+            Error::Parse(error) => write!(f, "could not parse input: {error}"),
+            Error::Solution(message) => write!(f, "could not solve puzzle: {message}"),
+            Error::Io(_) => write!(f, "input/output error"),
+            Error::Config(path, error) => write!(f, "invalid configuration in {}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) | Error::Config(_, error) => error.source(),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+#[macro_export]
+macro_rules! return_err_unless {
+    ($val:expr, $err:expr $(,)?) => {
+        match $val {
+            true => (),
+            false => return Err($err),
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let input = "first line\nsecond line\nthird";
+        let mut lines = input.lines();
+        lines.next();
+        let second = lines.next().unwrap();
+
+        assert_eq!(Location::of(input, input), Some(Location::new(1, 1)));
+        assert_eq!(Location::of(input, second), Some(Location::new(2, 1)));
+        assert_eq!(Location::of(input, &second[7..]), Some(Location::new(2, 8)));
+        assert_eq!(Location::of(input, "elsewhere"), None);
+
+        assert_eq!(Location::new(1, 3).offset(Location::new(2, 8)), Location::new(2, 10));
+        assert_eq!(Location::new(2, 3).offset(Location::new(2, 8)), Location::new(3, 3));
+    }
+}
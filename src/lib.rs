@@ -0,0 +1,66 @@
+//! My Advent of Code solutions as a library.
+//!
+//! Every solved day is registered in [`get_days`]; [`solve`] runs a single part of a day against
+//! an arbitrary input. The helpers shared between days live in [`tools`].
+
+pub mod aoc2023;
+pub mod aoc2024;
+pub mod config;
+pub mod day;
+pub mod error;
+pub mod logger;
+pub mod tools;
+pub mod visualize;
+
+use crate::day::{BoxedDay, Part, YearDay};
+pub use crate::day::Answer;
+pub use crate::error::Error;
+
+/// Returns all registered days, sorted by year and day.
+pub fn get_days() -> Vec<BoxedDay> {
+    let mut days: Vec<BoxedDay> = aoc2023::get_days_adv()
+        .into_iter()
+        .chain(aoc2024::get_days_adv())
+        .collect();
+    days.sort_by_key(|day| day.get_year_and_date());
+    days
+}
+
+/// Looks up the registered solution for a specific day.
+pub fn find_day(year_day: YearDay) -> Option<BoxedDay> {
+    get_days()
+        .into_iter()
+        .find(|day| day.get_year_and_date() == year_day)
+}
+
+/// Solves one part of a day for the given input.
+pub fn solve(year: u16, day: u16, part: Part, input: &str) -> Result<Answer, Error> {
+    let year_day = YearDay { year, day };
+    let solution = find_day(year_day).ok_or(Error::UnknownDay(year_day))?;
+
+    solution.answer(&part, input.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+        assert_eq!(solve(2024, 1, Part::Part1, input).unwrap(), 11);
+        assert_eq!(solve(2024, 1, Part::Part2, input).unwrap(), 31);
+        assert!(matches!(
+            solve(2015, 1, Part::Part1, input),
+            Err(Error::UnknownDay(YearDay { year: 2015, day: 1 }))
+        ));
+        assert!(matches!(
+            solve(2023, 1, Part::Part1, input),
+            Err(Error::Unsupported(YearDay { year: 2023, day: 1 }))
+        ));
+        assert!(matches!(solve(2024, 1, Part::Part1, "3 4\n4 x\n"), Err(Error::Parse(_))));
+        assert!(matches!(solve(2024, 1, Part::Part1, "3 4\n-4 3\n"), Err(Error::Parse(_))));
+        assert!(matches!(solve(2024, 2, Part::Part1, "7 6 4 2 1\n1 2 x7 8 9\n"), Err(Error::Parse(_))));
+    }
+}
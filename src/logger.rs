@@ -0,0 +1,128 @@
+use std::env;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// A minimal stderr logger for the `log` facade.
+///
+/// The default level is set by the runner (`-v`/`-q`); `RUST_LOG` can override it per module,
+/// e.g. `RUST_LOG=aoc::aoc2024::day9=trace` or `RUST_LOG=debug,aoc::tools=off`.
+pub struct Logger {
+    default: LevelFilter,
+    // (module prefix, level), the longest matching prefix wins
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    pub fn new(default: LevelFilter) -> Self {
+        Logger {
+            default,
+            directives: Vec::new(),
+        }
+    }
+
+    /// Creates a logger with the given default level and applies the filters from `RUST_LOG`.
+    pub fn from_env(default: LevelFilter) -> Self {
+        let mut logger = Self::new(default);
+        if let Ok(spec) = env::var("RUST_LOG") {
+            logger.parse_filters(&spec);
+        }
+        logger
+    }
+
+    /// Applies a comma-separated filter list like `warn,aoc::aoc2024=debug`. Entries without a
+    /// module replace the default level, unparseable entries are ignored.
+    pub fn parse_filters(&mut self, spec: &str) {
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        self.directives.push((module.trim().to_string(), level));
+                    }
+                }
+                None => {
+                    if let Ok(level) = directive.parse() {
+                        self.default = level;
+                    } else {
+                        // a bare module name enables everything for it
+                        self.directives.push((directive.to_string(), LevelFilter::Trace));
+                    }
+                }
+            }
+        }
+
+        // Sort by prefix length so the most specific directive is found first
+        self.directives.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(module, _)| {
+                target == module
+                    || (target.starts_with(module.as_str()) && target[module.len()..].starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, std::cmp::max)
+    }
+
+    /// Installs this logger as the global logger.
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        let max_level = self.max_level();
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{:<5} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Maps the runner's verbosity (`-v` counts up, `-q` counts down) to a level filter.
+pub fn level_for_verbosity(verbosity: i8) -> LevelFilter {
+    match verbosity {
+        i8::MIN..=-2 => LevelFilter::Off,
+        -1 => LevelFilter::Error,
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let mut logger = Logger::new(LevelFilter::Warn);
+        logger.parse_filters("aoc::aoc2024=debug, aoc::aoc2024::day9=trace,aoc::tools=off,bogus=loud");
+
+        assert_eq!(logger.level_for("aoc::aoc2024::day9"), LevelFilter::Trace);
+        assert_eq!(logger.level_for("aoc::aoc2024::day6"), LevelFilter::Debug);
+        assert_eq!(logger.level_for("aoc::aoc2024"), LevelFilter::Debug);
+        assert_eq!(logger.level_for("aoc::aoc20245"), LevelFilter::Warn);
+        assert_eq!(logger.level_for("aoc::tools"), LevelFilter::Off);
+        assert_eq!(logger.level_for("aoc::day"), LevelFilter::Warn);
+        assert_eq!(logger.max_level(), LevelFilter::Trace);
+
+        logger.parse_filters("info");
+        assert_eq!(logger.level_for("aoc::day"), LevelFilter::Info);
+    }
+}
@@ -1,16 +1,14 @@
-mod reduce;
-mod tools;
-mod aoc2023;
-mod aoc2024;
-mod day;
-
 use std::collections::HashMap;
 use std::env;
 use std::process::exit;
 use std::time::Instant;
-use crate::day::{BoxedDay, RunResultType, YearDay};
+use aoc::config::Config;
+use aoc::day::{BoxedDay, RunResultType, RunType, YearDay};
+use aoc::logger::{level_for_verbosity, Logger};
+use aoc::visualize;
+use aoc::visualize::Output;
 
-fn call_day(day: &BoxedDay) -> Vec<RunResultType>
+fn call_day(day: &BoxedDay, repeat: u16) -> Vec<RunResultType>
 {
     let YearDay { year: _, day: nday } = day.get_year_and_date();
     let start = Instant::now();
@@ -19,6 +17,12 @@ fn call_day(day: &BoxedDay) -> Vec<RunResultType>
 
     let elapsed = start.elapsed().as_secs_f64();
     println!("# day {nday} completed in {elapsed:.3}s");
+
+    if repeat > 1 && !result.contains(&RunResultType::Failed) {
+        if let Some(median) = day.median_time(repeat) {
+            println!("# day {nday} real input: median of {repeat} runs {:.3}s", median.as_secs_f64());
+        }
+    }
     result
 }
 
@@ -29,18 +33,15 @@ fn print_year_header(year: u16) {
 }
 
 fn main() {
-    let days_raw = vec!(
-        aoc2023::get_days_adv(),
-        aoc2024::get_days_adv()
-    );
-    let mut days : Vec<&BoxedDay> = days_raw.iter().flatten().collect();
+    let mut days = aoc::get_days();
 
     let args: Vec<String> = env::args().collect();
     let mut args_iter = args.iter();
     let argv0 = args_iter.next().unwrap();
-    let mut filter_years: Option<Vec<u16>> = None;
-    let mut filter_days: Option<Vec<u16>> = None;
-    let mut latest_only = false;
+    // Only explicitly given flags end up here, everything else comes from the config file
+    let mut cli = Config::default();
+    let mut verbosity: Option<i8> = None;
+    let mut show_config = false;
 
     while let Some(arg) = args_iter.next() {
         match arg.as_str() {
@@ -52,7 +53,7 @@ fn main() {
                         .collect();
 
                     if let Ok(possible_filter_years) = possible_filter_years {
-                        filter_years = Some(possible_filter_years);
+                        cli.years = Some(possible_filter_years);
                     } else {
                         println!("Error: Could not parse '{year_selection}' as year list!");
                         print_help(argv0);
@@ -72,7 +73,7 @@ fn main() {
                         .collect();
 
                     if let Ok(possible_filter_days) = possible_filter_days {
-                        filter_days = Some(possible_filter_days);
+                        cli.days = Some(possible_filter_days);
                     } else {
                         println!("Error: Could not parse '{day_selection}' as day list!");
                         print_help(argv0);
@@ -84,7 +85,54 @@ fn main() {
                     exit(1);
                 }
             }
-            "--latest" => { latest_only = true; }
+            "--latest" => { cli.latest = Some(true); }
+            "--no-latest" => { cli.latest = Some(false); }
+            "--visualize" => {
+                match args_iter.next().map(|mode| mode.parse::<Output>()) {
+                    Some(Ok(output)) => cli.visualize = Some(Some(output)),
+                    Some(Err(error)) => {
+                        println!("Error: {error}!");
+                        print_help(argv0);
+                        exit(1);
+                    }
+                    None => {
+                        println!("Error: visualize parameter without specified output!");
+                        print_help(argv0);
+                        exit(1);
+                    }
+                }
+            }
+            "--no-visualize" => { cli.visualize = Some(None); }
+            "--visualize-test" => { cli.visualize_test = Some(true); }
+            "--no-visualize-test" => { cli.visualize_test = Some(false); }
+            "--repeat" => {
+                match args_iter.next().map(|runs| runs.parse::<u16>()) {
+                    Some(Ok(runs)) if runs > 0 => cli.repeat = Some(runs),
+                    Some(_) => {
+                        println!("Error: repeat needs a positive number of runs!");
+                        print_help(argv0);
+                        exit(1);
+                    }
+                    None => {
+                        println!("Error: repeat parameter without specified number of runs!");
+                        print_help(argv0);
+                        exit(1);
+                    }
+                }
+            }
+            "config" => {
+                if args_iter.next().map(String::as_str) == Some("show") {
+                    show_config = true;
+                } else {
+                    println!("Error: config needs a subcommand!");
+                    print_help(argv0);
+                    exit(1);
+                }
+            }
+            "--verbose" => { change_verbosity(&mut verbosity, 1); }
+            "--quiet" => { change_verbosity(&mut verbosity, -1); }
+            flags if is_short_flag_group(flags, 'v') => { change_verbosity(&mut verbosity, flag_count(flags)); }
+            flags if is_short_flag_group(flags, 'q') => { change_verbosity(&mut verbosity, -flag_count(flags)); }
             unknown => {
                 println!("Error: Unknown parameter '{unknown}'!");
                 print_help(argv0);
@@ -93,51 +141,116 @@ fn main() {
         }
     }
 
+    cli.verbosity = verbosity;
+    let config = match Config::load() {
+        Ok(config) => config.overridden_by(cli),
+        Err(error) => {
+            println!("Error: {error}");
+            exit(1);
+        }
+    };
+
+    if show_config {
+        println!("{config}");
+        return;
+    }
+
+    Logger::from_env(level_for_verbosity(config.verbosity()))
+        .init()
+        .expect("Failed to initialize the logger!");
+
     days.retain(|day| {
         let YearDay { year, day } = day.get_year_and_date();
-        if filter_years.as_ref().is_some() && !filter_years.as_ref().unwrap().contains(&year) {
+        if config.years.as_ref().is_some_and(|years| !years.contains(&year)) {
             return false;
         }
-        if filter_days.as_ref().is_some() && !filter_days.as_ref().unwrap().contains(&day) {
+        if config.days.as_ref().is_some_and(|days| !days.contains(&day)) {
             return false;
         }
         true
     });
 
-    days.sort_by(|a, b| a.get_year_and_date().cmp(&b.get_year_and_date()));
-
     if days.is_empty() {
         println!("No days found matching current filter!");
         exit(2);
     }
 
-    if latest_only {
-        let current_day = days.last().unwrap();
-        call_day(current_day);
+    if config.latest() {
+        days.drain(..days.len() - 1);
+    }
+
+    if let Some(output) = config.visualize() {
+        let run_type = if config.visualize_test() { RunType::Test } else { RunType::Actual };
+        visualize_days(&days, output, &run_type);
+    } else if config.latest() {
+        call_day(days.last().unwrap(), config.repeat());
     } else {
-        run_list(&days);
+        run_list(&days, config.repeat());
+    }
+}
+
+/// Matches repeated short flags like `-vvv`.
+fn is_short_flag_group(arg: &str, flag: char) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == flag)
+}
+
+/// How often the flag is repeated in a group like `-vvv`, capped so it fits the verbosity.
+fn flag_count(flags: &str) -> i8 {
+    i8::try_from(flags.len() - 1).unwrap_or(i8::MAX)
+}
+
+fn change_verbosity(verbosity: &mut Option<i8>, by: i8) {
+    let level = verbosity.get_or_insert(0);
+    *level = level.saturating_add(by);
+}
+
+fn visualize_days(days: &[BoxedDay], output: &Output, run_type: &RunType) {
+    for day in days {
+        let YearDay { year, day: nday } = day.get_year_and_date();
+        match visualize::run(day.as_ref(), output, run_type) {
+            Ok(true) => {}
+            Ok(false) => println!("{year}, day {nday} has no visualisation"),
+            Err(error) => {
+                println!("Error: visualising {year}, day {nday} failed: {error}");
+                exit(1);
+            }
+        }
     }
 }
 
 fn print_help(argv0: &String)
 {
-    println!("Usage: {argv0} [--year n[,n,...]] [--day n[,n,...]] --latest");
+    println!("Usage: {argv0} [--year n[,n,...]] [--day n[,n,...]] [--[no-]latest] [-v|-q] [--visualize mode [--[no-]visualize-test] | --no-visualize] [--repeat n] [config show]");
     println!("  --year n[,n,...]");
     println!("    Comma-separated list of years to run.");
     println!("  --day n[,n,...]");
     println!("    Comma-separated list of days to run.");
-    println!("  --latest");
+    println!("  --latest, --no-latest");
     println!("    Only run the latest day in the latest year. Can be combined with year filter to run the last day of a specific year.");
+    println!("  -v, --verbose / -q, --quiet");
+    println!("    Increase or decrease the log level (default: warn). Can be repeated, i.e. -vvv for trace output.");
+    println!("    Use RUST_LOG for per-module filters, i.e. RUST_LOG=aoc::aoc2024::day9=trace.");
+    println!("  --visualize term|png[:dir]|gif[:dir]");
+    println!("    Instead of solving, visualise the selected days that support it. Images are written to ./visualizations by default.");
+    println!("  --no-visualize");
+    println!("    Solve as usual, even if aoc.toml asks for a visualisation.");
+    println!("  --visualize-test, --no-visualize-test");
+    println!("    Visualise the test input instead of the real one.");
+    println!("  --repeat n");
+    println!("    After each day, solve its real input n more times and print the median time of a run.");
+    println!("  config show");
+    println!("    Print the effective settings. Defaults for all flags can be set in aoc.toml, either next to Cargo.toml");
+    println!("    or in $XDG_CONFIG_HOME/aoc/. Flags given on the command line override the file.");
 }
 
-fn run_list(input: &Vec<&BoxedDay>) {
+fn run_list(input: &[BoxedDay], repeat: u16) {
     let mut header_year : u16 = 0;
     
     let now = Instant::now();
     let mut results: Vec<RunResultType> = Vec::new();
     let mut first = true;
 
-    for day in input.into_iter() {
+    for day in input.iter() {
         let YearDay { year, .. } = day.get_year_and_date();
         if year != header_year {
             print_year_header(year);
@@ -151,7 +264,7 @@ fn run_list(input: &Vec<&BoxedDay>) {
                 println!();
             }
         }
-        results.append(&mut call_day(day));
+        results.append(&mut call_day(day, repeat));
     }
 
     print!(
@@ -161,7 +274,7 @@ fn run_list(input: &Vec<&BoxedDay>) {
     print_results(&results);
 }
 
-fn print_results(results: &Vec<RunResultType>) {
+fn print_results(results: &[RunResultType]) {
     let frequencies = results
         .iter()
         .copied()
@@ -0,0 +1,431 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
+use crate::error::{Error, ParseError};
+
+/// A signed integer of arbitrary size, for answers that don't fit into an `i128`.
+///
+/// Division truncates towards zero like it does for the primitive integers.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// Base 2^32 digits, least significant first, without leading zeros. Empty for 0.
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt {
+            negative: false,
+            magnitude: self.magnitude.clone(),
+        }
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            base = &base * &base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// The quotient and remainder, see [`BigInt`] for the rounding. Panics if `divisor` is 0.
+    pub fn div_rem(&self, divisor: &BigInt) -> (BigInt, BigInt) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        let (quotient, remainder) = div_rem_magnitude(&self.magnitude, &divisor.magnitude);
+        (
+            BigInt::from_parts(self.negative != divisor.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        )
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> BigInt {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        BigInt {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    fn from_u128(negative: bool, mut value: u128) -> BigInt {
+        let mut magnitude = Vec::new();
+        while value > 0 {
+            magnitude.push(value as u32);
+            value >>= 32;
+        }
+        BigInt::from_parts(negative, magnitude)
+    }
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (idx, digit) in long.iter().enumerate() {
+        let sum = *digit as u64 + *short.get(idx).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, for `a >= b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (idx, digit) in a.iter().enumerate() {
+        let mut difference = *digit as i64 - *b.get(idx).unwrap_or(&0) as i64 - borrow;
+        borrow = (difference < 0) as i64;
+        difference += borrow << 32;
+        result.push(difference as u32);
+    }
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let product = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+/// Binary long division, one bit of the dividend at a time.
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..a.len() * 32).rev() {
+        // remainder = remainder * 2 + next bit
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for digit in remainder.iter_mut() {
+            let next_carry = *digit >> 31;
+            *digit = (*digit << 1) | carry;
+            carry = next_carry;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+
+        if compare_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+macro_rules! big_int_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from_u128(value < 0, (value as i128).unsigned_abs())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! big_int_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from_u128(false, value as u128)
+                }
+            }
+        )*
+    };
+}
+
+big_int_from_integer!(i8, i16, i32, i64, i128, isize);
+big_int_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = Error;
+
+    fn try_from(value: &BigInt) -> Result<Self, Self::Error> {
+        let out_of_range = || Error::solution(format!("{value} does not fit into an i128"));
+        if value.magnitude.len() > 4 {
+            return Err(out_of_range());
+        }
+        let magnitude = value
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u128, |acc, digit| (acc << 32) | *digit as u128);
+        if value.negative {
+            0i128.checked_sub_unsigned(magnitude).ok_or_else(out_of_range)
+        } else {
+            i128::try_from(magnitude).map_err(|_| out_of_range())
+        }
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseError;
+
+    /// Decimal digits with an optional sign.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::new(format!("'{s}' is not an integer")));
+        }
+
+        let mut magnitude: Vec<u32> = Vec::new();
+        // Nine digits at a time still fit into a digit of the magnitude
+        let first = digits.len() % 9;
+        let chunks = std::iter::once(&digits[..first])
+            .filter(|chunk| !chunk.is_empty())
+            .chain(digits.as_bytes()[first..].chunks(9).map(|chunk| std::str::from_utf8(chunk).unwrap()));
+        for chunk in chunks {
+            let scale = 10u32.pow(chunk.len() as u32);
+            magnitude = mul_magnitude(&magnitude, &[scale]);
+            magnitude = add_magnitude(&magnitude, &[chunk.parse().unwrap()]);
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+        }
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Nine decimal digits at a time, least significant first
+        let mut chunks: Vec<u32> = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let mut remainder = 0u64;
+            for digit in magnitude.iter_mut().rev() {
+                let value = (remainder << 32) | *digit as u64;
+                *digit = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+            chunks.push(remainder as u32);
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitude(&self.magnitude, &other.magnitude));
+        }
+        match compare_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_magnitude(&other.magnitude, &self.magnitude)),
+            _ => BigInt::from_parts(self.negative, sub_magnitude(&self.magnitude, &other.magnitude)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other.clone()
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_magnitude(&self.magnitude, &other.magnitude))
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+macro_rules! big_int_owned_ops {
+    ($($trait:ident $method:ident $assign_trait:ident $assign_method:ident),*) => {
+        $(
+            impl $trait for BigInt {
+                type Output = BigInt;
+
+                fn $method(self, other: BigInt) -> BigInt {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $assign_trait for BigInt {
+                fn $assign_method(&mut self, other: BigInt) {
+                    *self = (&*self).$method(&other);
+                }
+            }
+        )*
+    };
+}
+
+big_int_owned_ops!(Add add AddAssign add_assign, Sub sub SubAssign sub_assign, Mul mul MulAssign mul_assign);
+
+impl Div for BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: BigInt) -> BigInt {
+        &self / &other
+    }
+}
+
+impl Rem for BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: BigInt) -> BigInt {
+        &self % &other
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::zero(), |acc, value| acc + value)
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> Self {
+        iter.fold(BigInt::from(1), |acc, value| acc * value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_big_int() {
+        let factorial: BigInt = (1..=30u32).map(BigInt::from).product();
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        assert_eq!(big("2").pow(200).to_string(), "1606938044258990275541962092341162602522202993782792835301376");
+
+        for (a, b) in [(i128::MAX / 3, 977i128), (-123456789012345678901234, 98765432109876), (-5, -7), (0, 13), (7, -13)] {
+            let (x, y) = (BigInt::from(a), BigInt::from(b));
+            assert_eq!(&x + &y, BigInt::from(a + b));
+            assert_eq!(&x - &y, BigInt::from(a - b));
+            assert_eq!(&x / &y, BigInt::from(a / b));
+            assert_eq!(&x % &y, BigInt::from(a % b));
+            assert_eq!(x.cmp(&y), a.cmp(&b));
+            assert_eq!(i128::try_from(&x).unwrap(), a);
+            assert_eq!(x.to_string(), a.to_string());
+            assert_eq!(big(&a.to_string()), x);
+        }
+        assert_eq!(BigInt::from(-4_000_000_000i64) * BigInt::from(3_000_000_000u64), BigInt::from(-12_000_000_000_000_000_000i128));
+
+        let huge = big("-340282366920938463463374607431768211456");
+        assert_eq!(-huge.clone(), BigInt::from(u128::MAX) + BigInt::from(1));
+        assert!(i128::try_from(&huge).is_err());
+        assert_eq!(i128::try_from(&BigInt::from(i128::MIN)).unwrap(), i128::MIN);
+        assert_eq!((&huge / &big("18446744073709551616")).to_string(), "-18446744073709551616");
+        assert_eq!(huge.abs() % BigInt::from(1_000_000_007), BigInt::from(u128::MAX % 1_000_000_007 + 1));
+
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(big("+000123"), BigInt::from(123));
+        assert!("12a".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+
+        let mut sum = BigInt::zero();
+        sum += BigInt::from(u128::MAX);
+        sum -= BigInt::from(u128::MAX);
+        assert!(sum.is_zero() && !sum.is_negative());
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::{Display, Formatter};
+use crate::tools::{Direction, Matrix, Position};
+
+/// A fixed-size grid of booleans stored as one bit per cell, e.g. as a visited set in hot loops.
+/// Positions outside the grid are never set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        BitGrid {
+            words: vec![0; (width * height).div_ceil(64)],
+            width,
+            height,
+        }
+    }
+
+    /// The cells of `matrix` that satisfy `predicate`.
+    pub fn from_matrix<T, F>(matrix: &Matrix<T>, predicate: F) -> Self
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut grid = BitGrid::new(matrix.width(), matrix.height());
+        for (pos, value) in matrix.iter() {
+            if predicate(value) {
+                grid.insert(&pos);
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, position: &Position) -> Option<usize> {
+        if position.x < self.width && position.y < self.height {
+            Some(position.y * self.width + position.x)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.index(position)
+            .is_some_and(|idx| self.words[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    /// Sets a cell. Returns whether it was not set before, like `HashSet::insert`.
+    ///
+    /// Panics if the position is outside the grid.
+    pub fn insert(&mut self, position: &Position) -> bool {
+        let idx = self.index(position).expect("position outside of the grid");
+        let word = &mut self.words[idx / 64];
+        let bit = 1 << (idx % 64);
+        let newly_set = *word & bit == 0;
+        *word |= bit;
+        newly_set
+    }
+
+    /// Clears a cell. Returns whether it was set before.
+    pub fn remove(&mut self, position: &Position) -> bool {
+        let Some(idx) = self.index(position) else {
+            return false;
+        };
+        let word = &mut self.words[idx / 64];
+        let bit = 1 << (idx % 64);
+        let was_set = *word & bit != 0;
+        *word &= !bit;
+        was_set
+    }
+
+    /// Clears all cells.
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of set cells.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// All set cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.words.iter().enumerate().flat_map(move |(word_idx, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let idx = word_idx * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(Position {
+                    x: idx % self.width,
+                    y: idx / self.width,
+                })
+            })
+        })
+    }
+}
+
+/// Renders set cells as `#` and others as `.`.
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let set = self.contains(&Position { x, y });
+                write!(f, "{}", if set { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// One [`BitGrid`] per [`Direction`], e.g. for `(Position, Direction)` states.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DirectionalBitGrid {
+    grids: [BitGrid; 4],
+}
+
+impl DirectionalBitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        DirectionalBitGrid {
+            grids: std::array::from_fn(|_| BitGrid::new(width, height)),
+        }
+    }
+
+    fn grid(&self, direction: Direction) -> &BitGrid {
+        &self.grids[direction as usize]
+    }
+
+    fn grid_mut(&mut self, direction: Direction) -> &mut BitGrid {
+        &mut self.grids[direction as usize]
+    }
+
+    pub fn contains(&self, position: &Position, direction: Direction) -> bool {
+        self.grid(direction).contains(position)
+    }
+
+    /// Sets a state. Returns whether it was not set before.
+    pub fn insert(&mut self, position: &Position, direction: Direction) -> bool {
+        self.grid_mut(direction).insert(position)
+    }
+
+    pub fn remove(&mut self, position: &Position, direction: Direction) -> bool {
+        self.grid_mut(direction).remove(position)
+    }
+
+    pub fn clear(&mut self) {
+        self.grids.iter_mut().for_each(BitGrid::clear);
+    }
+
+    /// Whether the position is set in any direction.
+    pub fn contains_any(&self, position: &Position) -> bool {
+        self.grids.iter().any(|grid| grid.contains(position))
+    }
+
+    /// The positions set in any direction.
+    pub fn positions(&self) -> BitGrid {
+        let mut result = self.grids[0].clone();
+        for grid in self.grids[1..].iter() {
+            result.words.iter_mut().zip(grid.words.iter()).for_each(|(a, b)| *a |= b);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(10, 7);
+        assert!(grid.is_empty());
+        let positions = [Position { x: 0, y: 0 }, Position { x: 9, y: 6 }, Position { x: 3, y: 6 }, Position { x: 4, y: 2 }];
+        for pos in positions.iter() {
+            assert!(grid.insert(pos));
+            assert!(!grid.insert(pos));
+        }
+        assert_eq!(grid.len(), 4);
+        assert!(grid.contains(&Position { x: 4, y: 2 }));
+        assert!(!grid.contains(&Position { x: 2, y: 4 }));
+        assert!(!grid.contains(&Position { x: 10, y: 0 }));
+        let mut sorted = positions.to_vec();
+        sorted.sort_by_key(|pos| (pos.y, pos.x));
+        assert_eq!(grid.iter().collect::<Vec<_>>(), sorted);
+
+        assert!(grid.remove(&Position { x: 4, y: 2 }));
+        assert!(!grid.remove(&Position { x: 4, y: 2 }));
+        assert!(!grid.remove(&Position { x: 40, y: 2 }));
+        assert_eq!(grid.len(), 3);
+        grid.clear();
+        assert!(grid.is_empty());
+        assert_eq!(grid.iter().count(), 0);
+
+        let matrix = Matrix::char_matrix_from_string("#.\n.#").unwrap();
+        let grid = BitGrid::from_matrix(&matrix, |c| *c == '#');
+        assert_eq!(grid.to_string(), "#.\n.#\n");
+
+        let mut states = DirectionalBitGrid::new(3, 3);
+        let pos = Position { x: 1, y: 2 };
+        assert!(states.insert(&pos, Direction::North));
+        assert!(states.insert(&pos, Direction::East));
+        assert!(!states.insert(&pos, Direction::North));
+        assert!(!states.contains(&pos, Direction::South));
+        assert!(states.contains_any(&pos));
+        assert_eq!(states.positions().iter().collect::<Vec<_>>(), vec![pos.clone()]);
+        states.clear();
+        assert!(!states.contains_any(&pos));
+    }
+}
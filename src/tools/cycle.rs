@@ -0,0 +1,201 @@
+//! Cycle detection for sequences of states, `x0, x1 = step(x0), x2 = step(x1), ...`.
+//!
+//! [`brent`] and [`floyd`] only need `Eq` and constant memory, but expect an endless sequence.
+//! [`simulate`] keeps a hashed history of all states, which also allows sequences that end and
+//! looking up any state afterwards.
+
+use std::hash::Hash;
+use crate::tools::FastMap;
+
+/// From step `start` on, the states repeat every `length` steps.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as `step`.
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm, usually needing fewer steps than Floyd's.
+pub fn brent<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    // Find the length by letting the hare run ahead in powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // With the hare `length` steps ahead, both meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// Floyd's tortoise and hare.
+pub fn floyd<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle { start, length }
+}
+
+/// The state after `n` steps of an endless sequence. Only simulates up to the end of the first
+/// cycle, so `n` can be arbitrarily large.
+pub fn nth_state<S, F>(initial: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let cycle = brent(initial.clone(), &mut step);
+    (0..cycle.equivalent_step(n)).fold(initial, |state, _| step(&state))
+}
+
+/// All states of a sequence up to its first repetition or its end.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    states: Vec<S>,
+    cycle: Option<Cycle>,
+}
+
+impl<S> History<S> {
+    /// The cycle, or `None` if the sequence ended.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// The distinct states in the order they were reached.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The state after `n` steps, or `None` if the sequence ends before.
+    pub fn state_at(&self, n: usize) -> Option<&S> {
+        match self.cycle {
+            Some(cycle) => self.states.get(cycle.equivalent_step(n)),
+            None => self.states.get(n),
+        }
+    }
+}
+
+/// Runs `step` until a state repeats or `step` returns `None`, remembering every state.
+pub fn simulate<S, F>(initial: S, mut step: F) -> History<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    let mut seen: FastMap<S, usize> = FastMap::from_iter([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+
+    while let Some(next) = step(states.last().unwrap()) {
+        if let Some(start) = seen.get(&next) {
+            let cycle = Cycle {
+                start: *start,
+                length: states.len() - start,
+            };
+            return History { states, cycle: Some(cycle) };
+        }
+
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+
+    History { states, cycle: None }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_detection() {
+        // x -> x² + 1 mod 255 starting at 3: 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+        let step = |x: &u64| (x * x + 1) % 255;
+        let expected = Cycle { start: 2, length: 6 };
+
+        assert_eq!(brent(3, step), expected);
+        assert_eq!(floyd(3, step), expected);
+
+        let history = simulate(3, |x| Some(step(x)));
+        assert_eq!(history.cycle(), Some(expected));
+        assert_eq!(history.states(), &[3, 10, 101, 2, 5, 26, 167, 95]);
+        assert_eq!(history.state_at(8), Some(&101));
+        assert_eq!(history.state_at(1_000_000_000_000), Some(&5));
+        assert_eq!(nth_state(3, step, 1_000_000_000_000), 5);
+        assert_eq!(nth_state(3, step, 1), 10);
+
+        for n in 0..50 {
+            let simulated = (0..n).fold(3, |x, _| step(&x));
+            assert_eq!(nth_state(3, step, n), simulated);
+            assert_eq!(history.state_at(n), Some(&simulated));
+        }
+    }
+
+    #[test]
+    fn test_ending_sequence() {
+        let history = simulate(10, |x: &u32| x.checked_sub(3));
+        assert_eq!(history.cycle(), None);
+        assert_eq!(history.states(), &[10, 7, 4, 1]);
+        assert_eq!(history.state_at(3), Some(&1));
+        assert_eq!(history.state_at(4), None);
+
+        let fixed_point = simulate(0, |_| Some(0));
+        assert_eq!(fixed_point.cycle(), Some(Cycle { start: 0, length: 1 }));
+        assert_eq!(brent(7, |_| 7), Cycle { start: 0, length: 1 });
+        assert_eq!(floyd(7, |_| 7), Cycle { start: 0, length: 1 });
+    }
+}
@@ -0,0 +1,152 @@
+//! Polygons on the integer lattice, given as their vertices in walking order. The polygon is
+//! closed implicitly, from the last vertex back to the first. Extra vertices in the middle of an
+//! edge don't matter, so the tiles of a loop in a grid work as well as its corners.
+//!
+//! ```
+//! use aoc::tools::geometry::{boundary_points, doubled_area, interior_points};
+//! use aoc::tools::Point;
+//!
+//! // A 4×3 rectangle
+//! let corners = [Point::new(0, 0), Point::new(4, 0), Point::new(4, 3), Point::new(0, 3)];
+//! assert_eq!(doubled_area(&corners), 24);
+//! assert_eq!(boundary_points(&corners), 14);
+//! assert_eq!(interior_points(&corners), 6);
+//! ```
+
+use crate::tools::math::gcd;
+use crate::tools::Point;
+
+/// Where a point is relative to a polygon, see [`locate`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PointLocation {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices.iter().zip(vertices.iter().cycle().skip(1)).map(|(a, b)| (*a, *b))
+}
+
+/// Twice the signed area (shoelace formula). Positive if the vertices run clockwise on screen,
+/// i.e. with `y` growing downwards.
+pub fn signed_doubled_area(vertices: &[Point]) -> i64 {
+    edges(vertices).map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+}
+
+/// Twice the area, which is always an integer for lattice polygons.
+pub fn doubled_area(vertices: &[Point]) -> u64 {
+    signed_doubled_area(vertices).unsigned_abs()
+}
+
+/// The number of lattice points on the edges.
+pub fn boundary_points(vertices: &[Point]) -> u64 {
+    edges(vertices).map(|(a, b)| gcd((b - a).x, (b - a).y).unsigned_abs()).sum()
+}
+
+/// The number of lattice points strictly inside, by Pick's theorem `A = I + B / 2 - 1`.
+/// Only meaningful for simple polygons, i.e. without crossing edges. Degenerate polygons without
+/// area, like a single point or a line, have no interior points.
+pub fn interior_points(vertices: &[Point]) -> u64 {
+    let boundary = boundary_points(vertices);
+    if boundary == 0 {
+        return 0;
+    }
+    (doubled_area(vertices) + 2).saturating_sub(boundary) / 2
+}
+
+/// The number of lattice points inside or on the edges, e.g. the tiles dug out along a dig plan.
+pub fn enclosed_points(vertices: &[Point]) -> u64 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Whether `point` is inside the polygon, by counting the edges crossed by a ray to the right.
+pub fn locate(vertices: &[Point], point: Point) -> PointLocation {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        let (edge, to_point) = (b - a, point - a);
+        let cross = edge.x * to_point.y - edge.y * to_point.x;
+        let within = point.x >= a.x.min(b.x) && point.x <= a.x.max(b.x) && point.y >= a.y.min(b.y) && point.y <= a.y.max(b.y);
+        if cross == 0 && within {
+            return PointLocation::OnBoundary;
+        }
+
+        // Edges count if they cross the height of the point, with their lower end included and
+        // their upper end excluded, so vertices are not counted twice
+        if (a.y > point.y) != (b.y > point.y) {
+            // The ray crosses if the point lies left of the edge where it crosses that height
+            let crosses = if edge.y > 0 { cross > 0 } else { cross < 0 };
+            inside ^= crosses;
+        }
+    }
+
+    if inside {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_polygon() {
+        // An L shape:
+        // #####
+        // #...#
+        // #.###
+        // ###
+        let vertices = [
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 2),
+            Point::new(2, 2),
+            Point::new(2, 3),
+            Point::new(0, 3),
+        ];
+        assert_eq!(signed_doubled_area(&vertices), 20);
+        let reversed: Vec<Point> = vertices.iter().rev().copied().collect();
+        assert_eq!(signed_doubled_area(&reversed), -20);
+        assert_eq!(boundary_points(&vertices), 14);
+        assert_eq!(interior_points(&reversed), 4);
+        assert_eq!(enclosed_points(&vertices), 18);
+
+        // Every tile of the loop as a vertex gives the same result
+        let mut tiles: Vec<Point> = Vec::new();
+        for (a, b) in edges(&vertices) {
+            let step = (b - a).signum();
+            let mut tile = a;
+            while tile != b {
+                tiles.push(tile);
+                tile += step;
+            }
+        }
+        assert_eq!(tiles.len(), 14);
+        assert_eq!(interior_points(&tiles), 4);
+
+        for (point, location) in [
+            ((1, 1), PointLocation::Inside),
+            ((3, 1), PointLocation::Inside),
+            ((1, 2), PointLocation::Inside),
+            ((3, 3), PointLocation::Outside),
+            ((5, 0), PointLocation::Outside),
+            ((-1, 2), PointLocation::Outside),
+            ((2, 1), PointLocation::Inside),
+            ((4, 1), PointLocation::OnBoundary),
+            ((1, 3), PointLocation::OnBoundary),
+            ((0, 0), PointLocation::OnBoundary),
+        ] {
+            let point = Point::new(point.0, point.1);
+            assert_eq!(locate(&vertices, point), location, "{point}");
+            assert_eq!(locate(&tiles, point), location, "{point}");
+            assert_eq!(locate(&reversed, point), location, "{point}");
+        }
+
+        assert_eq!(interior_points(&[]), 0);
+        assert_eq!(interior_points(&[Point::new(1, 1)]), 0);
+        assert_eq!(interior_points(&[Point::new(0, 0), Point::new(3, 0)]), 0);
+        assert_eq!(interior_points(&[Point::new(0, 0), Point::new(2, 2), Point::new(4, 4)]), 0);
+    }
+}
@@ -0,0 +1,116 @@
+//! Hash maps and sets with a fast, non-cryptographic hasher.
+//!
+//! The puzzle inputs are trusted, so there is no need for the HashDoS protection of the std
+//! hasher. Building with the `std-hash` feature switches [`FastMap`] and [`FastSet`] back to it,
+//! e.g. to compare run times with `--repeat`.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hasher;
+
+/// The hasher used by rustc ("FxHash"): every word is mixed in with a rotation, an xor and a
+/// multiplication. Very fast for integer keys, but easy to attack with crafted keys.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in chunks.by_ref() {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.add_to_hash(i as u64);
+        self.add_to_hash((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Builds the hashers of [`FastMap`] and [`FastSet`].
+#[cfg(not(feature = "std-hash"))]
+pub type FastHashBuilder = std::hash::BuildHasherDefault<FxHasher>;
+
+/// Builds the hashers of [`FastMap`] and [`FastSet`].
+#[cfg(feature = "std-hash")]
+pub type FastHashBuilder = std::collections::hash_map::RandomState;
+
+/// A `HashMap` with a fast hasher. Create it with `FastMap::default()`.
+pub type FastMap<K, V> = HashMap<K, V, FastHashBuilder>;
+
+/// A `HashSet` with a fast hasher. Create it with `FastSet::default()`.
+pub type FastSet<T> = HashSet<T, FastHashBuilder>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+    fn fx_hash<T: Hash>(value: &T) -> u64 {
+        BuildHasherDefault::<FxHasher>::default().hash_one(value)
+    }
+
+    #[test]
+    fn test_fx_hasher() {
+        assert_eq!(fx_hash(&0u64), 0);
+        assert_eq!(fx_hash(&1u64), SEED);
+        assert_eq!(fx_hash(&(1u32, 2u32)), fx_hash(&(1u32, 2u32)));
+        assert_ne!(fx_hash(&(1u32, 2u32)), fx_hash(&(2u32, 1u32)));
+        assert_ne!(fx_hash(&"abcdefghi"), fx_hash(&"abcdefgh"));
+
+        let mut map: FastMap<&str, u32> = FastMap::default();
+        map.insert("a", 1);
+        *map.entry("a").or_default() += 1;
+        assert_eq!(map.get("a"), Some(&2));
+        let set: FastSet<u64> = (0..1000).map(|i| i % 10).collect();
+        assert_eq!(set.len(), 10);
+    }
+}
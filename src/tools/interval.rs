@@ -0,0 +1,345 @@
+//! Half-open integer intervals, normalised sets of them, and piecewise-linear maps between them.
+
+use std::fmt::{Debug, Formatter};
+use std::ops::Range;
+
+/// The integers `start..end`. Empty if `end <= start`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    pub fn with_length(start: i64, length: i64) -> Self {
+        Interval::new(start, start + length)
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The common part of both intervals, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let result = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!result.is_empty()).then_some(result)
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn shift(&self, offset: i64) -> Interval {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl From<Range<i64>> for Interval {
+    fn from(value: Range<i64>) -> Self {
+        Interval::new(value.start, value.end)
+    }
+}
+
+impl Debug for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// A set of integers stored as sorted intervals that neither overlap nor touch, so equal sets
+/// always have equal representations.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Adds all values of `interval`, coalescing it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, mut interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|other| other.end < interval.start);
+        let last = self.intervals.partition_point(|other| other.start <= interval.end);
+        if first < last {
+            interval.start = interval.start.min(self.intervals[first].start);
+            interval.end = interval.end.max(self.intervals[last - 1].end);
+        }
+        self.intervals.splice(first..last, [interval]);
+    }
+
+    /// Removes all values of `interval`.
+    pub fn remove(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|other| other.end <= interval.start);
+        let last = self.intervals.partition_point(|other| other.start < interval.end);
+        if first == last {
+            return;
+        }
+
+        let remaining = [
+            Interval::new(self.intervals[first].start, interval.start),
+            Interval::new(interval.end, self.intervals[last - 1].end),
+        ];
+        self.intervals
+            .splice(first..last, remaining.into_iter().filter(|rest| !rest.is_empty()));
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        other.iter().for_each(|interval| result.insert(*interval));
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            intervals.extend(a.intersection(&b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        // Intersections of normalised sets can't touch, so they are normalised as well
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = self.clone();
+        other.iter().for_each(|interval| result.remove(*interval));
+        result
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|interval| interval.end - 1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> {
+        self.intervals.iter()
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(value: Interval) -> Self {
+        IntervalSet::from_iter([value])
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut result = IntervalSet::new();
+        iter.into_iter().for_each(|interval| result.insert(interval));
+        result
+    }
+}
+
+/// A piecewise-linear map: values in a segment's source interval are shifted by its offset, all
+/// other values map to themselves.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RangeMap {
+    /// Sorted and non-overlapping. Segments with a zero offset are kept, so that later segments
+    /// overlapping them are still rejected.
+    segments: Vec<(Interval, i64)>,
+}
+
+impl RangeMap {
+    pub fn new() -> Self {
+        RangeMap::default()
+    }
+
+    /// Maps `source` to `source + offset`. If `source` overlaps an existing segment, the map is
+    /// left unchanged and the source interval of that segment is returned as the error.
+    pub fn insert(&mut self, source: Interval, offset: i64) -> Result<(), Interval> {
+        if source.is_empty() {
+            return Ok(());
+        }
+
+        let index = self.segments.partition_point(|(other, _)| other.end <= source.start);
+        if let Some((other, _)) = self.segments.get(index).filter(|(other, _)| other.overlaps(&source)) {
+            return Err(*other);
+        }
+        self.segments.insert(index, (source, offset));
+        Ok(())
+    }
+
+    /// [`RangeMap::insert`] for segments that are known not to overlap. Zero offsets are dropped.
+    fn insert_disjoint(&mut self, source: Interval, offset: i64) {
+        if offset != 0 {
+            self.insert(source, offset).expect("composed segments are disjoint");
+        }
+    }
+
+    /// Segments that actually move their values.
+    fn shifting_segments(&self) -> impl Iterator<Item = &(Interval, i64)> {
+        self.segments.iter().filter(|(_, offset)| *offset != 0)
+    }
+
+    /// The values that are not mapped to themselves.
+    pub fn domain(&self) -> IntervalSet {
+        self.shifting_segments().map(|(source, _)| *source).collect()
+    }
+
+    pub fn get(&self, value: i64) -> i64 {
+        let index = self.segments.partition_point(|(source, _)| source.end <= value);
+        match self.segments.get(index) {
+            Some((source, offset)) if source.contains(value) => value + offset,
+            _ => value,
+        }
+    }
+
+    /// All values that any value of `input` maps to.
+    pub fn image(&self, input: &IntervalSet) -> IntervalSet {
+        let mut result = input.difference(&self.domain());
+        for (source, offset) in self.shifting_segments() {
+            for interval in input.iter() {
+                if let Some(overlap) = interval.intersection(source) {
+                    result.insert(overlap.shift(*offset));
+                }
+            }
+        }
+        result
+    }
+
+    /// The map that first applies `self`, then `next`.
+    pub fn then(&self, next: &RangeMap) -> RangeMap {
+        let mut result = RangeMap::new();
+        for (source, offset) in self.shifting_segments() {
+            let image = source.shift(*offset);
+            let mut unmapped = IntervalSet::from(image);
+            for (next_source, next_offset) in next.shifting_segments() {
+                if let Some(overlap) = image.intersection(next_source) {
+                    result.insert_disjoint(overlap.shift(-offset), offset + next_offset);
+                    unmapped.remove(overlap);
+                }
+            }
+            for rest in unmapped.iter() {
+                result.insert_disjoint(rest.shift(-offset), *offset);
+            }
+        }
+
+        // Values that `self` leaves alone go through `next` unchanged
+        let untouched = next.domain().difference(&self.domain());
+        for (next_source, next_offset) in next.shifting_segments() {
+            for interval in untouched.iter() {
+                if let Some(overlap) = interval.intersection(next_source) {
+                    result.insert_disjoint(overlap, *next_offset);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(intervals: &[Range<i64>]) -> IntervalSet {
+        intervals.iter().cloned().map(Interval::from).collect()
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let a = set(&[0..5, 10..15, 5..7, 20..20]);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![(0..7).into(), (10..15).into()]);
+        assert_eq!(a.len(), 12);
+        assert!(a.contains(6) && !a.contains(7) && a.contains(10) && !a.contains(15));
+        assert_eq!((a.min(), a.max()), (Some(0), Some(14)));
+
+        let b = set(&[3..12, 14..30]);
+        assert_eq!(a.union(&b), IntervalSet::from(Interval::new(0, 30)));
+        assert_eq!(a.intersection(&b), set(&[3..7, 10..12, 14..15]));
+        assert_eq!(a.difference(&b), set(&[0..3, 12..14]));
+        assert_eq!(b.difference(&a), set(&[7..10, 15..30]));
+        assert_eq!(a.difference(&a), IntervalSet::new());
+
+        // compare with a brute force model
+        let values = |s: &IntervalSet| (-5..35).filter(|v| s.contains(*v)).collect::<Vec<_>>();
+        let model = |f: fn(bool, bool) -> bool| (-5..35).filter(|v| f(a.contains(*v), b.contains(*v))).collect::<Vec<_>>();
+        assert_eq!(values(&a.union(&b)), model(|x, y| x || y));
+        assert_eq!(values(&a.intersection(&b)), model(|x, y| x && y));
+        assert_eq!(values(&a.difference(&b)), model(|x, y| x && !y));
+    }
+
+    #[test]
+    fn test_range_map() {
+        // 2023 day5 example: seed-to-soil and soil-to-fertilizer
+        let mut seed_to_soil = RangeMap::new();
+        seed_to_soil.insert(Interval::with_length(98, 2), 50 - 98).unwrap();
+        seed_to_soil.insert(Interval::with_length(50, 48), 52 - 50).unwrap();
+        let mut soil_to_fertilizer = RangeMap::new();
+        soil_to_fertilizer.insert(Interval::with_length(15, 37), -15).unwrap();
+        soil_to_fertilizer.insert(Interval::with_length(52, 2), 37 - 52).unwrap();
+        soil_to_fertilizer.insert(Interval::with_length(0, 15), 39).unwrap();
+
+        assert_eq!([79, 14, 55, 13].map(|v| seed_to_soil.get(v)), [81, 14, 57, 13]);
+
+        let composed = seed_to_soil.then(&soil_to_fertilizer);
+        for value in -10..120 {
+            assert_eq!(composed.get(value), soil_to_fertilizer.get(seed_to_soil.get(value)));
+        }
+
+        let seeds = set(&[79..93, 55..68]);
+        let image = composed.image(&seeds);
+        let expected: IntervalSet = (79..93)
+            .chain(55..68)
+            .map(|v| Interval::with_length(composed.get(v), 1))
+            .collect();
+        assert_eq!(image, expected);
+
+        // Overlapping segments are rejected and leave the map as it was
+        let before = seed_to_soil.clone();
+        assert_eq!(seed_to_soil.insert(Interval::with_length(90, 10), 1), Err(Interval::with_length(50, 48)));
+        assert_eq!(seed_to_soil, before);
+        assert_eq!(seed_to_soil.insert(Interval::with_length(0, 50), 1), Ok(()));
+
+        // Identity segments do not change any value, but still count for overlaps
+        let mut identity = RangeMap::new();
+        assert_eq!(identity.insert(Interval::new(10, 20), 0), Ok(()));
+        assert_eq!(identity.insert(Interval::new(15, 25), 5), Err(Interval::new(10, 20)));
+        assert_eq!(identity.get(15), 15);
+        assert_eq!(identity.domain(), IntervalSet::new());
+        assert_eq!(identity.then(&seed_to_soil).get(60), 62);
+    }
+}
@@ -0,0 +1,140 @@
+use std::marker::PhantomData;
+use std::str::FromStr;
+use crate::error::Error;
+use crate::tools::parse_token;
+
+/// Iterator over all integers in a string, see [`ints`].
+#[derive(Debug, Clone)]
+pub struct Ints<'a, T> {
+    input: &'a str,
+    offset: usize,
+    signed: bool,
+    item: PhantomData<T>,
+}
+
+/// Extracts every integer from `input`, ignoring everything in between, so
+/// `"Game 3: 8 green, -6 blue"` yields 3, 8 and -6.
+///
+/// A `-` right before the digits is a sign if `T` is signed and the `-` does not follow another
+/// digit, so ranges like `1-3` yield 1 and 3. Numbers that don't fit into `T` are errors pointing to
+/// the number.
+pub fn ints<T>(input: &str) -> Ints<'_, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    Ints {
+        input,
+        offset: 0,
+        signed: "-1".parse::<T>().is_ok(),
+        item: PhantomData,
+    }
+}
+
+impl<T> Iterator for Ints<'_, T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.input.as_bytes();
+        let digits = self.offset + bytes[self.offset..].iter().position(u8::is_ascii_digit)?;
+        let end = digits + bytes[digits..].iter().position(|b| !b.is_ascii_digit()).unwrap_or(bytes.len() - digits);
+        self.offset = end;
+
+        let is_sign = |idx: usize| bytes[idx] == b'-' && (idx == 0 || !bytes[idx - 1].is_ascii_digit());
+        let start = match digits {
+            0 => 0,
+            _ if self.signed && is_sign(digits - 1) => digits - 1,
+            _ => digits,
+        };
+
+        Some(parse_token(self.input, &self.input[start..end]))
+    }
+}
+
+/// The integers of each line, see [`ints`]. Lines without integers become empty lists.
+pub fn ints_per_line<T>(input: &str) -> Result<Vec<Vec<T>>, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    input
+        .lines()
+        .map(|line| ints(line).collect::<Result<_, _>>().map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+/// The whitespace-separated integers of `input`. Unlike [`ints`], this is strict: every token
+/// must be an integer of type `T`, anything else is an error pointing to the token.
+pub fn separated_ints<T>(input: &str) -> impl Iterator<Item = Result<T, Error>> + '_
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    input.split_whitespace().map(|token| parse_token(input, token))
+}
+
+/// The integers of each line, see [`separated_ints`]. Blank lines become empty lists.
+pub fn separated_ints_per_line<T>(input: &str) -> Result<Vec<Vec<T>>, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    input
+        .lines()
+        .map(|line| separated_ints(line).collect::<Result<_, _>>().map_err(|e| e.within(input, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Location;
+
+    fn all<T>(input: &str) -> Vec<T>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        ints(input).collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(all::<u32>("Card 1: 41 48 | 83 86"), vec![1, 41, 48, 83, 86]);
+        assert_eq!(all::<i128>("Game 3: 8 green, -6 blue"), vec![3, 8, -6]);
+        assert_eq!(all::<u8>("Game 3: 8 green, -6 blue"), vec![3, 8, 6]);
+        assert_eq!(all::<i64>("mul(2,4)&mul[3,7]!-1-2--3 x-4"), vec![2, 4, 3, 7, -1, 2, -3, -4]);
+        assert_eq!(all::<i32>("-17"), vec![-17]);
+        assert_eq!(all::<i32>("no numbers - here"), Vec::<i32>::new());
+        assert_eq!(all::<i128>("170141183460469231731687303715884105727"), vec![i128::MAX]);
+
+        assert_eq!(
+            ints_per_line::<i64>("0 3 6\n\n-1 -2\n").unwrap(),
+            vec![vec![0, 3, 6], vec![], vec![-1, -2]]
+        );
+        match ints_per_line::<u8>("1 2\n3 256") {
+            Err(Error::Parse(error)) => assert_eq!(error.location, Some(Location::new(2, 3))),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_separated_ints() {
+        let all = |input| separated_ints::<i64>(input).collect::<Result<Vec<_>, _>>();
+        assert_eq!(all(" 7 -6\t4  \n2 ").unwrap(), vec![7, -6, 4, 2]);
+        assert!(all("1 x7 8").is_err());
+        assert!(all("1, 2").is_err());
+
+        assert_eq!(separated_ints_per_line::<u64>("3   4\n\n4   3\n").unwrap(), vec![vec![3, 4], vec![], vec![4, 3]]);
+        for input in ["7 6 4\n1 2 x7", "7 6 4\n1 2 -4"] {
+            match separated_ints_per_line::<u64>(input) {
+                Err(Error::Parse(error)) => assert_eq!(error.location, Some(Location::new(2, 5))),
+                _ => panic!("expected a parse error for {input:?}"),
+            }
+        }
+    }
+}
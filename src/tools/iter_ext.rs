@@ -0,0 +1,210 @@
+use std::hash::Hash;
+use crate::tools::FastMap;
+
+/// Iterator adapters and consumers that the days keep needing, available on every iterator.
+pub trait IterExt: Iterator + Sized {
+    /// How often each item occurs.
+    fn counts(self) -> FastMap<Self::Item, usize>
+    where
+        Self::Item: Eq + Hash,
+    {
+        let mut counts = FastMap::default();
+        for item in self {
+            *counts.entry(item).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The smallest and the largest item in one pass, or `None` if there are no items. Like
+    /// [`Iterator::min`] and [`Iterator::max`], ties resolve to the first minimum and the last
+    /// maximum.
+    fn minmax(mut self) -> Option<(Self::Item, Self::Item)>
+    where
+        Self::Item: Ord + Clone,
+    {
+        let first = self.next()?;
+        Some(self.fold((first.clone(), first), |(min, max), item| {
+            if item < min {
+                (item, max)
+            } else if item >= max {
+                (min, item)
+            } else {
+                (min, max)
+            }
+        }))
+    }
+
+    /// Each item together with the next one, so `1, 2, 3` yields `(1, 2)` and `(2, 3)`.
+    fn tuple_windows(mut self) -> TupleWindows<Self>
+    where
+        Self::Item: Clone,
+    {
+        let previous = self.next();
+        TupleWindows { iter: self, previous }
+    }
+
+    /// Every unordered pair of items, in order of their positions: `1, 2, 3` yields `(1, 2)`,
+    /// `(1, 3)` and `(2, 3)`.
+    fn pairs(self) -> Pairs<Self::Item>
+    where
+        Self::Item: Clone,
+    {
+        Pairs { items: self.collect(), first: 0, second: 1 }
+    }
+
+    /// Every selection of `k` items, keeping their order, in lexicographic order of their
+    /// positions. For `k = 0` that is a single empty selection.
+    fn combinations(self, k: usize) -> Combinations<Self::Item>
+    where
+        Self::Item: Clone,
+    {
+        let items: Vec<Self::Item> = self.collect();
+        let indices = (k <= items.len()).then(|| (0..k).collect());
+        Combinations { items, indices }
+    }
+
+    /// All items sorted, stable for equal items.
+    fn sorted(self) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.sort();
+        items
+    }
+
+    /// All items sorted by `key`, stable for equal keys.
+    fn sorted_by_key<K, F>(self, key: F) -> Vec<Self::Item>
+    where
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.sort_by_key(key);
+        items
+    }
+
+    /// The items as an array, or `None` unless there are exactly `N` of them. Handy for
+    /// destructuring: `let Some([a, b]) = line.split(' ').collect_array() else { ... }`.
+    fn collect_array<const N: usize>(self) -> Option<[Self::Item; N]> {
+        let items: Vec<Self::Item> = self.take(N + 1).collect();
+        items.try_into().ok()
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+/// See [`IterExt::tuple_windows`].
+#[derive(Debug, Clone)]
+pub struct TupleWindows<I: Iterator> {
+    iter: I,
+    previous: Option<I::Item>,
+}
+
+impl<I> Iterator for TupleWindows<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = (I::Item, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.iter.next()?;
+        let previous = self.previous.replace(current.clone())?;
+        Some((previous, current))
+    }
+}
+
+/// See [`IterExt::pairs`].
+#[derive(Debug, Clone)]
+pub struct Pairs<T> {
+    items: Vec<T>,
+    first: usize,
+    second: usize,
+}
+
+impl<T: Clone> Iterator for Pairs<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.second >= self.items.len() {
+            self.first += 1;
+            self.second = self.first + 1;
+            if self.second >= self.items.len() {
+                return None;
+            }
+        }
+        let pair = (self.items[self.first].clone(), self.items[self.second].clone());
+        self.second += 1;
+        Some(pair)
+    }
+}
+
+/// See [`IterExt::combinations`].
+#[derive(Debug, Clone)]
+pub struct Combinations<T> {
+    items: Vec<T>,
+    /// The positions of the next selection, `None` once all were yielded.
+    indices: Option<Vec<usize>>,
+}
+
+impl<T: Clone> Iterator for Combinations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.indices.as_mut()?;
+        let result = indices.iter().map(|idx| self.items[*idx].clone()).collect();
+
+        // Advance the rightmost position that still has room, and reset all after it
+        let (n, k) = (self.items.len(), indices.len());
+        match (0..k).rev().find(|&i| indices[i] < n - k + i) {
+            Some(i) => {
+                indices[i] += 1;
+                for j in i + 1..k {
+                    indices[j] = indices[j - 1] + 1;
+                }
+            }
+            None => self.indices = None,
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_iter_ext() {
+        let counts = "abracadabra".chars().counts();
+        assert_eq!((counts[&'a'], counts[&'b'], counts.get(&'z')), (5, 2, None));
+
+        assert_eq!([3, 1, 4, 1, 5].into_iter().minmax(), Some((1, 5)));
+        assert_eq!([7].into_iter().minmax(), Some((7, 7)));
+        assert_eq!(std::iter::empty::<u8>().minmax(), None);
+
+        let windows: Vec<_> = [1, 2, 4, 7].into_iter().tuple_windows().map(|(a, b)| b - a).collect();
+        assert_eq!(windows, vec![1, 2, 3]);
+        assert_eq!([1].into_iter().tuple_windows().count(), 0);
+
+        let pairs: Vec<_> = "abc".chars().pairs().collect();
+        assert_eq!(pairs, vec![('a', 'b'), ('a', 'c'), ('b', 'c')]);
+        assert_eq!("a".chars().pairs().count(), 0);
+        assert_eq!("".chars().pairs().count(), 0);
+        assert_eq!((0..10).pairs().count(), 45);
+
+        let combinations: Vec<String> = "abcd".chars().combinations(3).map(String::from_iter).collect();
+        assert_eq!(combinations, vec!["abc", "abd", "acd", "bcd"]);
+        assert_eq!((0..6).combinations(2).count(), 15);
+        assert_eq!((0..3).combinations(0).collect::<Vec<_>>(), vec![Vec::<i32>::new()]);
+        assert_eq!((0..3).combinations(4).count(), 0);
+
+        assert_eq!(["bb", "a", "ccc", "d"].into_iter().sorted_by_key(|s| s.len()), vec!["a", "d", "bb", "ccc"]);
+        assert_eq!([3, 1, 2].into_iter().sorted(), vec![1, 2, 3]);
+
+        assert_eq!("1 2".split(' ').collect_array(), Some(["1", "2"]));
+        assert_eq!("1 2 3".split(' ').collect_array::<2>(), None);
+        assert_eq!("1".split(' ').collect_array::<2>(), None);
+    }
+}
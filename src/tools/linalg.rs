@@ -0,0 +1,210 @@
+//! Exact solutions for small systems of linear equations, e.g. how often to press two buttons to
+//! reach a target, or where two straight paths cross.
+//!
+//! ```
+//! use aoc::tools::linalg::{solve, Solution};
+//!
+//! // 94a + 22b = 8400, 34a + 67b = 5400
+//! let solution = solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]);
+//! assert_eq!(solution.integers(), Some(vec![80, 40]));
+//! ```
+
+use crate::tools::{Position, PositionDelta, Rational};
+
+/// The outcome of [`solve`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Solution {
+    /// Exactly one value per unknown.
+    Unique(Vec<Rational>),
+    /// Infinitely many solutions, because there are not enough independent equations.
+    Underdetermined,
+    /// No solution, because the equations contradict each other.
+    Inconsistent,
+}
+
+impl Solution {
+    pub fn unique(self) -> Option<Vec<Rational>> {
+        match self {
+            Solution::Unique(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// The unique solution, if all of its values are integers.
+    pub fn integers(self) -> Option<Vec<i128>> {
+        self.unique()?.into_iter().map(Rational::to_integer).collect()
+    }
+}
+
+/// Solves `coefficients · x = constants` by Gaussian elimination with exact fractions. Every row
+/// of `coefficients` is one equation and needs one value per unknown. Panics if the rows differ in
+/// length or don't match the number of constants.
+pub fn solve<T>(coefficients: &[Vec<T>], constants: &[T]) -> Solution
+where
+    T: Into<Rational> + Copy,
+{
+    assert_eq!(coefficients.len(), constants.len(), "expected one constant per equation");
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    assert!(coefficients.iter().all(|row| row.len() == unknowns), "expected equations of equal length");
+
+    // Augmented rows, the constant last
+    let mut rows: Vec<Vec<Rational>> = coefficients
+        .iter()
+        .zip(constants.iter())
+        .map(|(row, constant)| row.iter().chain(std::iter::once(constant)).map(|value| (*value).into()).collect())
+        .collect();
+
+    // Reduced row echelon form
+    let mut rank = 0;
+    for column in 0..unknowns {
+        let Some(pivot) = (rank..rows.len()).find(|row| !rows[*row][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+
+        let scale = rows[rank][column];
+        rows[rank].iter_mut().for_each(|value| *value = *value / scale);
+        let pivot_row = rows[rank].clone();
+        for (idx, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if idx != rank && !factor.is_zero() {
+                for (value, pivot_value) in row.iter_mut().zip(pivot_row.iter()).skip(column) {
+                    *value = *value - factor * *pivot_value;
+                }
+            }
+        }
+        rank += 1;
+    }
+
+    // Rows without unknowns left must not claim 0 = c for c ≠ 0
+    if rows[rank..].iter().any(|row| !row[unknowns].is_zero()) {
+        Solution::Inconsistent
+    } else if rank < unknowns {
+        Solution::Underdetermined
+    } else {
+        Solution::Unique(rows[..rank].iter().map(|row| row[unknowns]).collect())
+    }
+}
+
+/// The points `origin + t · direction` for any `t`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Line {
+    pub origin: Position,
+    pub direction: PositionDelta,
+}
+
+/// How two [`Line`]s meet, see [`Line::intersect`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Intersection {
+    /// A single point, at `t` along the first line and `u` along the second one.
+    At { t: Rational, u: Rational },
+    /// The lines are the same, so every point is shared.
+    Same,
+    /// The lines never meet.
+    Parallel,
+    /// At least one of the lines has no direction, so it is just a point and not a line.
+    Degenerate,
+}
+
+impl Line {
+    pub fn new(origin: Position, direction: PositionDelta) -> Self {
+        Line { origin, direction }
+    }
+
+    /// The line through two different positions, with `t = 0` at `a` and `t = 1` at `b`. For
+    /// equal positions, the line is [degenerate](Line::is_degenerate).
+    pub fn through(a: &Position, b: &Position) -> Self {
+        Line::new(a.clone(), b.diff(a))
+    }
+
+    /// Whether the direction is zero, so all `t` give the same point.
+    pub fn is_degenerate(&self) -> bool {
+        self.direction.x == 0 && self.direction.y == 0
+    }
+
+    /// The coordinates at `t`, which can be fractions.
+    pub fn at(&self, t: Rational) -> (Rational, Rational) {
+        (
+            Rational::from(self.origin.x) + t * Rational::from(self.direction.x),
+            Rational::from(self.origin.y) + t * Rational::from(self.direction.y),
+        )
+    }
+
+    /// The position at `t`, if it has integer, non-negative coordinates.
+    pub fn position_at(&self, t: Rational) -> Option<Position> {
+        let (x, y) = self.at(t);
+        Some(Position {
+            x: x.to_integer()?.try_into().ok()?,
+            y: y.to_integer()?.try_into().ok()?,
+        })
+    }
+
+    pub fn intersect(&self, other: &Line) -> Intersection {
+        if self.is_degenerate() || other.is_degenerate() {
+            return Intersection::Degenerate;
+        }
+
+        // origin + t · direction = other.origin + u · other.direction
+        let offset = other.origin.diff(&self.origin);
+        let coefficients = [
+            vec![self.direction.x as i128, -(other.direction.x as i128)],
+            vec![self.direction.y as i128, -(other.direction.y as i128)],
+        ];
+        match solve(&coefficients, &[offset.x as i128, offset.y as i128]) {
+            Solution::Unique(values) => Intersection::At { t: values[0], u: values[1] },
+            Solution::Underdetermined => Intersection::Same,
+            Solution::Inconsistent => Intersection::Parallel,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(&[vec![26, 67], vec![66, 21]], &[12748, 12176]).integers(), None);
+        assert_eq!(
+            solve(&[vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]], &[8, -11, -3]).integers(),
+            Some(vec![2, 3, -1])
+        );
+        assert_eq!(
+            solve(&[vec![0, 2], vec![3, 0]], &[1, 1]),
+            Solution::Unique(vec![Rational::new(1, 3), Rational::new(1, 2)])
+        );
+
+        // Dependent and contradicting equations
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 6]), Solution::Underdetermined);
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::Inconsistent);
+        // More equations than unknowns
+        assert_eq!(solve(&[vec![1], vec![2], vec![3]], &[2, 4, 6]).integers(), Some(vec![2]));
+        assert_eq!(solve(&[vec![1], vec![2], vec![3]], &[2, 4, 5]), Solution::Inconsistent);
+        // Fewer equations than unknowns
+        assert_eq!(solve(&[vec![1, 1, 1]], &[1]), Solution::Underdetermined);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let diagonal = Line::through(&Position { x: 0, y: 0 }, &Position { x: 4, y: 4 });
+        let anti_diagonal = Line::new(Position { x: 0, y: 3 }, PositionDelta::new(1, -1));
+        let half = Rational::new(1, 2);
+        let Intersection::At { t, u } = diagonal.intersect(&anti_diagonal) else {
+            panic!("expected the diagonals to cross");
+        };
+        assert_eq!((t, u), (Rational::new(3, 8), Rational::new(3, 2)));
+        assert_eq!(diagonal.at(t), (Rational::from(3) * half, Rational::from(3) * half));
+        assert_eq!(diagonal.position_at(t), None);
+        assert_eq!(diagonal.position_at(half), Some(Position { x: 2, y: 2 }));
+
+        let shifted = Line::new(Position { x: 1, y: 0 }, PositionDelta::new(2, 2));
+        assert_eq!(diagonal.intersect(&shifted), Intersection::Parallel);
+        let backwards = Line::new(Position { x: 7, y: 7 }, PositionDelta::new(-1, -1));
+        assert_eq!(diagonal.intersect(&backwards), Intersection::Same);
+
+        let point = Line::through(&Position { x: 2, y: 2 }, &Position { x: 2, y: 2 });
+        assert!(point.is_degenerate());
+        assert_eq!(diagonal.intersect(&point), Intersection::Degenerate);
+        assert_eq!(point.intersect(&anti_diagonal), Intersection::Degenerate);
+    }
+}
@@ -0,0 +1,250 @@
+//! Number theory helpers: gcd/lcm, extended Euclid, the Chinese Remainder Theorem, modular
+//! arithmetic and integer square roots.
+//!
+//! The modular functions work on `i128` and never overflow for positive moduli, products that
+//! don't fit are computed step by step in [`mod_mul`].
+
+use std::ops::{Div, Mul, Rem, Sub};
+
+/// Primitive integers, signed or unsigned. `Default` must be zero.
+pub trait Integer:
+    Copy + Ord + Default + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Rem<Output = Self>
+{
+}
+
+impl<T> Integer for T where
+    T: Copy + Ord + Default + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T>
+{
+}
+
+fn abs<T: Integer>(value: T) -> T {
+    if value < T::default() {
+        T::default() - value
+    } else {
+        value
+    }
+}
+
+/// The greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (abs(a), abs(b));
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, always non-negative. Is 0 if either argument is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::default() || b == T::default() {
+        return T::default();
+    }
+    // divide first to keep the intermediate value small
+    abs(a / gcd(a, b) * b)
+}
+
+/// The gcd of all values, or `None` if there are none.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().map(abs).reduce(gcd)
+}
+
+/// The lcm of all values, or `None` if there are none.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().map(abs).reduce(lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `modulus` in `0..modulus`, if `a` and `modulus` are coprime. Panics
+/// if `modulus` is not positive.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    assert!(modulus > 0, "modulus must be positive");
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(modulus))
+}
+
+/// `a * b mod modulus` in `0..modulus`, without overflowing. Panics if `modulus` is not positive.
+pub fn mod_mul(a: i128, b: i128, modulus: i128) -> i128 {
+    assert!(modulus > 0, "modulus must be positive");
+    let (a, b) = (a.rem_euclid(modulus), b.rem_euclid(modulus));
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    // Double and add; both operands stay below 2^127, so their sum fits into a u128
+    let modulus = modulus as u128;
+    let (mut a, mut b) = (a as u128, b as u128);
+    let mut result = 0u128;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = (result + a) % modulus;
+        }
+        a = (a + a) % modulus;
+        b >>= 1;
+    }
+    result as i128
+}
+
+/// `base^exponent mod modulus` by repeated squaring, in `0..modulus`. Panics if `modulus` is not
+/// positive.
+pub fn mod_pow(base: i128, mut exponent: u64, modulus: i128) -> i128 {
+    assert!(modulus > 0, "modulus must be positive");
+    let mut result = 1 % modulus;
+    let mut base = base.rem_euclid(modulus);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mod_mul(result, base, modulus);
+        }
+        base = mod_mul(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for all `(residue, modulus)` pairs.
+///
+/// The moduli do not have to be coprime. Returns `(x, m)` where `m` is the lcm of all moduli and
+/// `x` in `0..m` is the smallest solution; all solutions are `x + k * m`. Returns `None` if the
+/// congruences contradict each other, a modulus is not positive or `m` does not fit into an
+/// `i128`. An empty system is solved by every `x`, i.e. `(0, 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = (i128, i128)>) -> Option<(i128, i128)> {
+    let mut result = (0, 1);
+    for (residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (x, m) = result;
+        let residue = residue.rem_euclid(modulus);
+
+        // x + m * k ≡ residue (mod modulus)  <=>  m * k ≡ residue - x (mod modulus)
+        let (g, inverse, _) = extended_gcd(m, modulus);
+        let difference = residue - x;
+        if difference % g != 0 {
+            return None;
+        }
+
+        let reduced = modulus / g;
+        let k = mod_mul(difference / g, inverse, reduced);
+        let combined = m.checked_mul(reduced)?;
+        result = ((x + m * k).rem_euclid(combined), combined);
+    }
+    Some(result)
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Newton's method, starting above the root so the estimate decreases monotonically
+    let mut estimate = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (estimate + n / estimate) / 2;
+        if next >= estimate {
+            return estimate;
+        }
+        estimate = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(12u64, 0), 12);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0usize, 6), 0);
+        assert_eq!(gcd_all([24, 36, 60]), Some(12));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all(Vec::<i32>::new()), None);
+
+        for (a, b) in [(240, 46), (-240, 46), (17, 5), (0, 7), (7, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        // Fermat: a^(p-1) ≡ 1 (mod p)
+        assert_eq!(mod_pow(123_456_789, 1_000_000_006, 1_000_000_007), 1);
+
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 4)]), Some((3, 4)));
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(1, 0)]), None);
+        assert_eq!(crt([(1, 3), (1, -5)]), None);
+        // The lcm of the moduli would not fit
+        assert_eq!(crt([(1, 1 << 100), (3, (1 << 89) - 1)]), None);
+
+        // Large coprime moduli, where the products only fit with mod_mul
+        let (p, q) = ((1 << 61) - 1, (1 << 64) + 1);
+        assert_eq!(crt([(5, p), (5, q)]), Some((5, p * q)));
+        assert_eq!(crt([(p - 1, p), (q - 1, q)]), Some((p * q - 1, p * q)));
+        assert_eq!(mod_mul(p * q - 1, p * q - 1, p * q), 1);
+        assert_eq!(mod_pow(2, 127, i128::MAX), 1);
+
+        for x in 0..360 {
+            let congruences = [(x % 8, 8), (x % 9, 9), (x % 12, 12), (x % 10, 10)];
+            assert_eq!(crt(congruences), Some((x, 360)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn test_mod_pow_zero_modulus() {
+        mod_pow(2, 3, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn test_mod_inverse_negative_modulus() {
+        mod_inverse(3, -11);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n, "isqrt({n}) = {root}");
+        }
+        assert_eq!(isqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+}
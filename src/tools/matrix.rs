@@ -0,0 +1,609 @@
+use std::fmt::{Display, Formatter};
+use crate::error::{Error, Location, ParseError};
+use crate::return_none_unless;
+use crate::tools::{Connectivity, Position, PositionDelta};
+
+/// A rectangular grid, stored row by row. `x` is the column and `y` the row, starting top left.
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+pub struct Matrix<T> {
+    data: Vec<T>,
+    xsize: usize,
+    ysize: usize,
+}
+
+impl<T> Matrix<T> {
+    pub fn create(inp_data: &mut [Vec<T>]) -> Result<Self, Error> {
+        let ysize = inp_data.len();
+        let xsize = inp_data.first().ok_or(Error::parse("matrix has no rows"))?.len();
+
+        let mut data = Vec::with_capacity(ysize * xsize);
+        for (y, vec) in inp_data.iter_mut().enumerate() {
+            if vec.len() != xsize {
+                return Err(ParseError::new(format!(
+                    "matrix row has {} columns instead of {xsize}",
+                    vec.len()
+                ))
+                .at(Some(Location::new(y + 1, 1)))
+                .into());
+            }
+            data.append(vec);
+        }
+
+        Ok(Matrix { data, xsize, ysize })
+    }
+
+    /// Creates a matrix from row-major data. Returns `None` if the data doesn't match the size.
+    pub fn from_vec(xsize: usize, ysize: usize, data: Vec<T>) -> Option<Self> {
+        return_none_unless!(xsize.checked_mul(ysize)? == data.len());
+        Some(Matrix { data, xsize, ysize })
+    }
+
+    pub fn from_fn<F>(xsize: usize, ysize: usize, mut generator: F) -> Self
+    where
+        F: FnMut(Position) -> T,
+    {
+        let data = (0..ysize)
+            .flat_map(|y| (0..xsize).map(move |x| Position { x, y }))
+            .map(&mut generator)
+            .collect();
+        Matrix { data, xsize, ysize }
+    }
+
+    pub fn from_string<F>(input: &str, mapper: F) -> Result<Self, Error>
+    where
+        F: Fn(char) -> T,
+    {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut data: Vec<Vec<T>> = Vec::with_capacity(lines.capacity());
+
+        for line in lines {
+            data.push(line.chars().map(&mapper).collect());
+        }
+
+        Matrix::create(&mut data)
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.xsize && y < self.ysize {
+            Some(y * self.xsize + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.data.get(self.index(x, y)?)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        let index = self.index(x, y)?;
+        self.data.get_mut(index)
+    }
+
+    /// Replaces the value at the given coordinates and returns the previous one, or `None` if
+    /// the coordinates are out of bounds (in which case nothing is changed).
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        Some(std::mem::replace(self.get_mut(x, y)?, value))
+    }
+
+    pub fn get_position(&self, position: &Position) -> Option<&T> {
+        self.get(position.x, position.y)
+    }
+
+    pub fn get_position_mut(&mut self, position: &Position) -> Option<&mut T> {
+        self.get_mut(position.x, position.y)
+    }
+
+    pub fn set_position(&mut self, position: &Position, value: T) -> Option<T> {
+        self.set(position.x, position.y, value)
+    }
+
+    pub fn get_dimensions(&self) -> (usize, usize) {
+        (self.xsize, self.ysize)
+    }
+
+    pub fn width(&self) -> usize {
+        self.xsize
+    }
+
+    pub fn height(&self) -> usize {
+        self.ysize
+    }
+
+    pub fn checked_position_apply(
+        &self,
+        position: &Position,
+        delta: &PositionDelta,
+    ) -> Option<Position> {
+        let next = position.apply(delta)?;
+        if self.validate_position(&next) {
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    pub fn validate_position(&self, position: &Position) -> bool {
+        position.x < self.xsize && position.y < self.ysize
+    }
+
+    /// The neighbours of a position that lie inside the matrix, clockwise starting at north.
+    pub fn neighbours(
+        &self,
+        position: &Position,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = (Position, &T)> + '_ {
+        let position = position.clone();
+        connectivity.deltas().iter().filter_map(move |delta| {
+            let next = self.checked_position_apply(&position, delta)?;
+            let item = self.get_position(&next)?;
+            Some((next, item))
+        })
+    }
+
+    /// Walks from `position` in steps of `delta` until leaving the matrix. The starting position
+    /// itself is not included. A zero `delta` never gets anywhere, so the ray is empty.
+    pub fn ray(&self, position: &Position, delta: PositionDelta) -> Ray<'_, T> {
+        Ray {
+            matrix: self,
+            position: position.clone(),
+            delta,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        return_none_unless!(y < self.ysize);
+        Some(&self.data[y * self.xsize..(y + 1) * self.xsize])
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [T]> {
+        return_none_unless!(y < self.ysize);
+        Some(&mut self.data[y * self.xsize..(y + 1) * self.xsize])
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        // chunks panics for a size of 0, but then there aren't any items anyway
+        self.data.chunks(self.xsize.max(1))
+    }
+
+    /// The items of column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> Option<impl DoubleEndedIterator<Item = &T>> {
+        return_none_unless!(x < self.xsize);
+        Some(self.data.iter().skip(x).step_by(self.xsize))
+    }
+
+    pub fn column_mut(&mut self, x: usize) -> Option<impl DoubleEndedIterator<Item = &mut T>> {
+        return_none_unless!(x < self.xsize);
+        Some(self.data.iter_mut().skip(x).step_by(self.xsize))
+    }
+
+    pub fn iter(&self) -> MatrixIterator<'_, T> {
+        self.into_iter()
+    }
+
+    /// Like `iter`, but hands out mutable references.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Position, &mut T)> {
+        let xsize = self.xsize;
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(move |(idx, item)| (Position { x: idx % xsize, y: idx / xsize }, item))
+    }
+
+    pub fn map<U, F>(&self, mut mapper: F) -> Matrix<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        self.map_indexed(|_, item| mapper(item))
+    }
+
+    pub fn map_indexed<U, F>(&self, mut mapper: F) -> Matrix<U>
+    where
+        F: FnMut(Position, &T) -> U,
+    {
+        Matrix {
+            data: self.iter().map(|(pos, item)| mapper(pos, item)).collect(),
+            xsize: self.xsize,
+            ysize: self.ysize,
+        }
+    }
+
+    /// A read-only view of the `xsize`×`ysize` rectangle starting at (`x`, `y`). Returns `None`
+    /// if the rectangle does not fit into this matrix.
+    pub fn view(&self, x: usize, y: usize, xsize: usize, ysize: usize) -> Option<MatrixView<'_, T>> {
+        return_none_unless!(x.checked_add(xsize)? <= self.xsize && y.checked_add(ysize)? <= self.ysize);
+        Some(MatrixView {
+            matrix: self,
+            xoffset: x,
+            yoffset: y,
+            xsize,
+            ysize,
+        })
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    pub fn new(xsize: usize, ysize: usize, value: T) -> Self {
+        Matrix {
+            data: vec![value; xsize * ysize],
+            xsize,
+            ysize,
+        }
+    }
+
+    /// Mirrors the matrix along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.ysize, self.xsize, |Position { x, y }| self.get(y, x).unwrap().clone())
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.ysize, self.xsize, |Position { x, y }| {
+            self.get(y, self.ysize - 1 - x).unwrap().clone()
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        Self::from_fn(self.ysize, self.xsize, |Position { x, y }| {
+            self.get(self.xsize - 1 - y, x).unwrap().clone()
+        })
+    }
+
+    pub fn rotate_half(&self) -> Self {
+        let mut data = self.data.clone();
+        data.reverse();
+        Matrix { data, ..*self }
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let mut result = self.clone();
+        for y in 0..self.ysize {
+            result.row_mut(y).unwrap().reverse();
+        }
+        result
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Matrix {
+            data: self.rows().rev().flatten().cloned().collect(),
+            ..*self
+        }
+    }
+}
+
+impl<T> Display for Matrix<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Matrix {}×{}", self.xsize, self.ysize)?;
+
+        for row in self.rows() {
+            for item in row {
+                write!(f, "{}", item)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Eq,
+{
+    pub fn find_first(&self, needle: &T) -> Option<Position> {
+        for (pos, item) in self.into_iter() {
+            if item == needle {
+                return Some(pos);
+            }
+        }
+
+        None
+    }
+}
+
+impl Matrix<char> {
+    pub fn char_matrix_from_string(input: &str) -> Result<Self, Error> {
+        Self::from_string(input, |c| c)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Matrix<T> {
+    type Item = (Position, &'a T);
+    type IntoIter = MatrixIterator<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (xsize, _) = self.get_dimensions();
+        MatrixIterator {
+            matrix: self,
+            xsize,
+            ypos: 0,
+            xpos: 0,
+        }
+    }
+}
+
+pub struct MatrixIterator<'a, T> {
+    matrix: &'a Matrix<T>,
+    xpos: usize,
+    ypos: usize,
+    xsize: usize,
+}
+
+impl<'a, T> Iterator for MatrixIterator<'a, T> {
+    type Item = (Position, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.matrix.get(self.xpos, self.ypos)?;
+        let position = Position {
+            x: self.xpos,
+            y: self.ypos,
+        };
+
+        self.xpos += 1;
+        if self.xpos >= self.xsize {
+            self.xpos = 0;
+            self.ypos += 1;
+        }
+
+        Some((position, item))
+    }
+}
+
+/// Iterator returned by [`Matrix::ray`].
+pub struct Ray<'a, T> {
+    matrix: &'a Matrix<T>,
+    position: Position,
+    delta: PositionDelta,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Position, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return_none_unless!(self.delta != PositionDelta::new(0, 0));
+        let next = self.matrix.checked_position_apply(&self.position, &self.delta)?;
+        let item = self.matrix.get_position(&next)?;
+        self.position = next.clone();
+        Some((next, item))
+    }
+}
+
+/// A rectangular part of a [`Matrix`]. Coordinates are relative to the top left of the view.
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+    matrix: &'a Matrix<T>,
+    xoffset: usize,
+    yoffset: usize,
+    xsize: usize,
+    ysize: usize,
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        return_none_unless!(x < self.xsize && y < self.ysize);
+        self.matrix.get(self.xoffset + x, self.yoffset + y)
+    }
+
+    pub fn get_position(&self, position: &Position) -> Option<&'a T> {
+        self.get(position.x, position.y)
+    }
+
+    pub fn get_dimensions(&self) -> (usize, usize) {
+        (self.xsize, self.ysize)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&'a [T]> {
+        return_none_unless!(y < self.ysize);
+        let row = self.matrix.row(self.yoffset + y)?;
+        Some(&row[self.xoffset..self.xoffset + self.xsize])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.ysize).map(|y| self.row(y).unwrap())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &'a T)> + '_ {
+        self.rows()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, item)| (Position { x, y }, item)))
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T>
+    where
+        T: Clone,
+    {
+        Matrix {
+            data: self.rows().flatten().cloned().collect(),
+            xsize: self.xsize,
+            ysize: self.ysize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tools::Direction8;
+
+    /// SplitMix64, good enough to generate test cases without pulling in a crate.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        }
+
+        fn below(&mut self, max: usize) -> usize {
+            (self.next() % max as u64) as usize
+        }
+    }
+
+    /// Random matrices together with the `Vec<Vec<_>>` they were built from.
+    fn cases() -> impl Iterator<Item = (Matrix<u32>, Vec<Vec<u32>>)> {
+        let mut rng = Rng(2024);
+        (0..300).map(move |_| {
+            let (xsize, ysize) = (rng.below(9) + 1, rng.below(9) + 1);
+            let model: Vec<Vec<u32>> = (0..ysize)
+                .map(|_| (0..xsize).map(|_| rng.below(1000) as u32).collect())
+                .collect();
+            (Matrix::create(&mut model.clone()).unwrap(), model)
+        })
+    }
+
+    #[test]
+    fn test_indexing_matches_model() {
+        for (mut matrix, mut model) in cases() {
+            let (xsize, ysize) = (model[0].len(), model.len());
+            assert_eq!(matrix.get_dimensions(), (xsize, ysize));
+
+            for y in 0..ysize + 2 {
+                for x in 0..xsize + 2 {
+                    assert_eq!(matrix.get(x, y), model.get(y).and_then(|row| row.get(x)));
+                    assert_eq!(matrix.get_mut(x, y).is_some(), x < xsize && y < ysize);
+                }
+            }
+
+            for (y, row) in model.iter().enumerate() {
+                assert_eq!(matrix.row(y).unwrap(), row.as_slice());
+            }
+            assert!(matrix.row(ysize).is_none());
+            for x in 0..xsize {
+                let column: Vec<u32> = model.iter().map(|row| row[x]).collect();
+                assert_eq!(matrix.column(x).unwrap().copied().collect::<Vec<_>>(), column);
+            }
+            assert!(matrix.column(xsize).is_none());
+
+            let flattened: Vec<(Position, u32)> = model
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, v)| (Position { x, y }, *v)))
+                .collect();
+            assert_eq!(matrix.iter().map(|(pos, v)| (pos, *v)).collect::<Vec<_>>(), flattened);
+
+            let (x, y) = (xsize - 1, ysize / 2);
+            assert_eq!(matrix.set(x, y, 5000), Some(model[y][x]));
+            assert_eq!(matrix.set(xsize, y, 5000), None);
+            model[y][x] = 5000;
+            for (pos, item) in matrix.iter_mut() {
+                *item += pos.x as u32;
+            }
+            for column in 0..xsize {
+                matrix.column_mut(column).unwrap().for_each(|item| *item += 1);
+            }
+            let expected = model
+                .iter()
+                .map(|row| row.iter().enumerate().map(|(x, v)| v + x as u32 + 1).collect())
+                .collect::<Vec<Vec<u32>>>();
+            assert_eq!(matrix, Matrix::create(&mut expected.clone()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_transformations() {
+        for (matrix, model) in cases() {
+            let (xsize, ysize) = matrix.get_dimensions();
+
+            let transposed = matrix.transpose();
+            assert_eq!(transposed.get_dimensions(), (ysize, xsize));
+            let clockwise = matrix.rotate_clockwise();
+            let counter_clockwise = matrix.rotate_counter_clockwise();
+            let flipped_h = matrix.flip_horizontal();
+            let flipped_v = matrix.flip_vertical();
+            let half = matrix.rotate_half();
+
+            for (y, row) in model.iter().enumerate() {
+                for (x, value) in row.iter().enumerate() {
+                    assert_eq!(transposed.get(y, x), Some(value));
+                    assert_eq!(clockwise.get(ysize - 1 - y, x), Some(value));
+                    assert_eq!(counter_clockwise.get(y, xsize - 1 - x), Some(value));
+                    assert_eq!(flipped_h.get(xsize - 1 - x, y), Some(value));
+                    assert_eq!(flipped_v.get(x, ysize - 1 - y), Some(value));
+                    assert_eq!(half.get(xsize - 1 - x, ysize - 1 - y), Some(value));
+                }
+            }
+
+            assert_eq!(transposed.transpose(), matrix);
+            assert_eq!(clockwise.rotate_counter_clockwise(), matrix);
+            assert_eq!(clockwise.rotate_clockwise(), half);
+            assert_eq!(transposed.flip_horizontal(), clockwise);
+            assert_eq!(flipped_h.flip_vertical(), half);
+        }
+    }
+
+    #[test]
+    fn test_map_and_views() {
+        let mut rng = Rng(31);
+        for (matrix, model) in cases() {
+            let (xsize, ysize) = matrix.get_dimensions();
+
+            let mapped = matrix.map_indexed(|pos, v| (pos, v * 2));
+            assert_eq!(mapped.get_dimensions(), (xsize, ysize));
+            for (pos, (mapped_pos, doubled)) in mapped.iter() {
+                assert_eq!(&pos, mapped_pos);
+                assert_eq!(*doubled, model[pos.y][pos.x] * 2);
+            }
+            assert_eq!(matrix.map(|v| v.to_string()).get(0, 0), Some(&model[0][0].to_string()));
+
+            let (x, y) = (rng.below(xsize), rng.below(ysize));
+            let (w, h) = (rng.below(xsize - x) + 1, rng.below(ysize - y) + 1);
+            let view = matrix.view(x, y, w, h).unwrap();
+            assert_eq!(view.get_dimensions(), (w, h));
+            for vy in 0..h + 1 {
+                for vx in 0..w + 1 {
+                    let expected = (vx < w && vy < h).then(|| &model[y + vy][x + vx]);
+                    assert_eq!(view.get(vx, vy), expected);
+                }
+            }
+            let sub: Vec<Vec<u32>> = model[y..y + h].iter().map(|row| row[x..x + w].to_vec()).collect();
+            assert_eq!(view.to_matrix(), Matrix::create(&mut sub.clone()).unwrap());
+            assert_eq!(view.iter().count(), w * h);
+            assert!(matrix.view(x, y, xsize - x + 1, h).is_none());
+            assert!(matrix.view(x, y, w, usize::MAX).is_none());
+        }
+    }
+
+    #[test]
+    fn test_neighbours_and_rays() {
+        let matrix = Matrix::from_fn(4, 3, |pos| pos.y * 4 + pos.x);
+        let neighbours = |x, y, connectivity| {
+            matrix.neighbours(&Position { x, y }, connectivity).map(|(_, v)| *v).collect::<Vec<_>>()
+        };
+
+        assert_eq!(neighbours(0, 0, Connectivity::Four), vec![1, 4]);
+        assert_eq!(neighbours(0, 0, Connectivity::Eight), vec![1, 5, 4]);
+        assert_eq!(neighbours(1, 1, Connectivity::Four), vec![1, 6, 9, 4]);
+        assert_eq!(neighbours(1, 1, Connectivity::Eight), vec![1, 2, 6, 10, 9, 8, 4, 0]);
+        assert_eq!(neighbours(3, 2, Connectivity::Eight), vec![7, 10, 6]);
+
+        let ray = |x, y, direction: Direction8| {
+            matrix.ray(&Position { x, y }, direction.to_position_delta()).map(|(_, v)| *v).collect::<Vec<_>>()
+        };
+        assert_eq!(ray(0, 0, Direction8::East), vec![1, 2, 3]);
+        assert_eq!(ray(0, 0, Direction8::SouthEast), vec![5, 10]);
+        assert_eq!(ray(3, 2, Direction8::North), vec![7, 3]);
+        assert_eq!(ray(0, 1, Direction8::West), Vec::<usize>::new());
+        assert_eq!(matrix.ray(&Position { x: 1, y: 1 }, PositionDelta::new(0, 0)).count(), 0);
+        assert_eq!(
+            matrix.ray(&Position { x: 0, y: 0 }, Direction8::SouthEast.to_position_delta()).last(),
+            Some((Position { x: 2, y: 2 }, &10))
+        );
+    }
+
+    #[test]
+    fn test_constructors() {
+        assert!(Matrix::from_vec(2, 3, vec![0; 5]).is_none());
+        let matrix = Matrix::from_vec(3, 2, (0..6).collect()).unwrap();
+        assert_eq!(matrix, Matrix::from_fn(3, 2, |pos| pos.y * 3 + pos.x));
+        assert_eq!(matrix.get(2, 1), Some(&5));
+        assert_eq!(Matrix::new(2, 2, 'x').row(1), Some(&['x', 'x'][..]));
+        assert!(Matrix::<char>::char_matrix_from_string("ab\nc").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Memoisation for recursive functions.
+//!
+//! The function body becomes a closure that gets the [`Memo`] back, so it can recurse through it:
+//!
+//! ```
+//! use aoc::tools::memo::Memo;
+//!
+//! fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+//!     memo.get_or_compute(n, |memo| match n {
+//!         0 | 1 => n,
+//!         _ => fibonacci(n - 1, memo) + fibonacci(n - 2, memo),
+//!     })
+//! }
+//!
+//! let mut memo = Memo::new();
+//! assert_eq!(fibonacci(90, &mut memo), 2880067194370816120);
+//! assert_eq!(memo.stats().misses, 91);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use crate::tools::FastHashBuilder;
+
+/// A cache of computed values, counting how often it was hit.
+///
+/// Uses the [`FastHashBuilder`] of [`FastMap`](crate::tools::FastMap) unless `S` says otherwise.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V, S = FastHashBuilder> {
+    cache: HashMap<K, V, S>,
+    hits: usize,
+    misses: usize,
+}
+
+/// How well a [`Memo`] worked, e.g. for [`report_stats`](crate::day::report_stats).
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
+
+impl MemoStats {
+    /// The fraction of lookups that were answered from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+impl Display for MemoStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} entries",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0,
+            self.entries
+        )
+    }
+}
+
+impl<K, V, S: Default> Memo<K, V, S> {
+    pub fn new() -> Self {
+        Memo::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S: Default> Default for Memo<K, V, S> {
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl<K, V, S> Memo<K, V, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Memo {
+            cache: HashMap::with_hasher(hasher),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forgets all values and resets the statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+impl<K, V, S> Memo<K, V, S>
+where
+    K: Eq + Hash,
+    V: Clone,
+    S: BuildHasher,
+{
+    /// The cached value for `key`, or the result of `compute`, which is cached afterwards.
+    ///
+    /// `compute` may call back into the memo for other keys. A key must not depend on itself,
+    /// that would recurse endlessly.
+    pub fn get_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+
+        self.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// The cached value for `key`, without computing or counting anything.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn paths(x: u32, y: u32, memo: &mut Memo<(u32, u32), u64>) -> u64 {
+        memo.get_or_compute((x, y), |memo| {
+            if x == 0 || y == 0 {
+                1
+            } else {
+                paths(x - 1, y, memo) + paths(x, y - 1, memo)
+            }
+        })
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(2, 2, &mut memo), 6);
+        assert_eq!(memo.stats(), MemoStats { hits: 1, misses: 8, entries: 8 });
+        assert_eq!(memo.get(&(1, 1)), Some(&2));
+        assert_eq!(memo.get(&(2, 3)), None);
+
+        assert_eq!(paths(16, 16, &mut memo), 601080390);
+        assert_eq!(memo.len(), 17 * 17 - 1);
+        assert_eq!(memo.stats().misses, 17 * 17 - 1);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), MemoStats::default());
+        assert_eq!(MemoStats::default().to_string(), "0 hits, 0 misses (0.0% hit rate), 0 entries");
+    }
+}
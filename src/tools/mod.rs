@@ -0,0 +1,339 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::str::FromStr;
+use crate::error::{Error, Location, ParseError};
+
+mod big_int;
+mod bit_grid;
+pub mod cycle;
+pub mod geometry;
+mod hash;
+pub mod interval;
+mod ints;
+mod iter_ext;
+pub mod linalg;
+pub mod math;
+mod matrix;
+pub mod memo;
+pub mod parse;
+mod point;
+pub mod poly;
+mod rational;
+pub mod search;
+pub mod sections;
+mod sparse_grid;
+
+pub use big_int::BigInt;
+pub use bit_grid::{BitGrid, DirectionalBitGrid};
+pub use hash::{FastHashBuilder, FastMap, FastSet, FxHasher};
+pub use ints::{ints, ints_per_line, separated_ints, separated_ints_per_line, Ints};
+pub use iter_ext::{Combinations, IterExt, Pairs, TupleWindows};
+pub use matrix::{Matrix, MatrixIterator, MatrixView, Ray};
+pub use point::Point;
+pub use rational::Rational;
+pub use sparse_grid::SparseGrid;
+
+pub fn get_input(day: &str, year: u16) -> Result<String, String> {
+    let fname = format!("./inputs/{}/day{}.txt", year, day);
+    let result = fs::read_to_string(fname.clone());
+    if let Ok(result) = result {
+        Ok(result)
+    } else {
+        Err(format!("Could not read file {}!", fname))
+    }
+}
+
+pub fn get_input_or_panic(day: &str, year: u16) -> String {
+    match get_input(day, year) {
+        Ok(input) => input,
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Parses `token`, which must be a subslice of `input`. On failure, the error points to the token.
+pub fn parse_token<T>(input: &str, token: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    token.parse().map_err(|err| {
+        ParseError::new(format!("invalid value '{token}'"))
+            .at(Location::of(input, token))
+            .caused_by(err)
+            .into()
+    })
+}
+
+#[macro_export]
+macro_rules! return_none_unless {
+    ($val:expr $(,)?) => {
+        match $val {
+            true => (),
+            false => return None,
+        }
+    };
+}
+
+pub type Coordinate = usize;
+pub type CoordinateDelta = isize;
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+pub struct Position {
+    pub x: Coordinate,
+    pub y: Coordinate,
+}
+
+impl Position {
+    pub fn apply(&self, delta: &PositionDelta) -> Option<Position> {
+        let x = self.x.checked_add_signed(delta.x)?;
+        let y = self.y.checked_add_signed(delta.y)?;
+        Some(Position { x, y })
+    }
+
+    pub fn diff(&self, other: &Position) -> PositionDelta {
+        PositionDelta {
+            x: (self.x as CoordinateDelta) - (other.x as CoordinateDelta),
+            y: (self.y as CoordinateDelta) - (other.y as CoordinateDelta),
+        }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x={}, y={})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Hash, Copy, Clone, Eq, PartialEq)]
+pub struct PositionDelta {
+    pub x: CoordinateDelta,
+    pub y: CoordinateDelta,
+}
+
+impl PositionDelta {
+    pub fn new(x: CoordinateDelta, y: CoordinateDelta) -> Self {
+        PositionDelta { x, y }
+    }
+
+    pub fn reverse(&self) -> PositionDelta {
+        PositionDelta {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl Display for PositionDelta {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Δ(x={}, y={})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Hash, Copy, Clone, Eq, PartialEq)]
+#[rustfmt::skip] // :>
+pub enum Direction {
+         North,
+    West,      East,
+         South,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::West,
+        Direction::South,
+    ];
+
+    pub fn reverse(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+        }
+    }
+
+    pub fn to_position_delta(self) -> PositionDelta {
+        match self {
+            Direction::North => PositionDelta { x: 0, y: -1 },
+            Direction::West => PositionDelta { x: -1, y: 0 },
+            Direction::East => PositionDelta { x: 1, y: 0 },
+            Direction::South => PositionDelta { x: 0, y: 1 },
+        }
+    }
+
+    pub fn get_others(&self) -> &[Direction; 3] {
+        match self {
+            Direction::North => &[Direction::West, Direction::East, Direction::South],
+            Direction::West => &[Direction::North, Direction::East, Direction::South],
+            Direction::East => &[Direction::West, Direction::North, Direction::South],
+            Direction::South => &[Direction::West, Direction::East, Direction::North],
+        }
+    }
+
+    /// The direction after a 90° turn clockwise.
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    /// The direction after a 90° turn counter-clockwise.
+    pub fn turn_left(&self) -> Direction {
+        self.turn_right().reverse()
+    }
+}
+
+/// Like [`Direction`], but including the diagonals.
+#[derive(Debug, Hash, Copy, Clone, Eq, PartialEq)]
+#[rustfmt::skip]
+pub enum Direction8 {
+    NorthWest, North, NorthEast,
+    West,             East,
+    SouthWest, South, SouthEast,
+}
+
+impl Direction8 {
+    /// All directions, clockwise starting at north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    pub const DIAGONALS: [Direction8; 4] = [
+        Direction8::NorthEast,
+        Direction8::SouthEast,
+        Direction8::SouthWest,
+        Direction8::NorthWest,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|d| *d == self).unwrap()
+    }
+
+    /// Rotates clockwise in steps of 45°; negative steps rotate counter-clockwise.
+    pub fn rotate(self, steps: isize) -> Direction8 {
+        Self::ALL[(self.index() as isize + steps).rem_euclid(8) as usize]
+    }
+
+    /// The direction after a 45° turn clockwise.
+    pub fn rotate_right(self) -> Direction8 {
+        self.rotate(1)
+    }
+
+    /// The direction after a 45° turn counter-clockwise.
+    pub fn rotate_left(self) -> Direction8 {
+        self.rotate(-1)
+    }
+
+    pub fn turn_right(self) -> Direction8 {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction8 {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Direction8 {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn to_position_delta(self) -> PositionDelta {
+        let (x, y) = match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        };
+        PositionDelta { x, y }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+/// Which cells count as neighbours of a cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Only the cells sharing an edge.
+    Four,
+    /// Including the diagonal cells.
+    Eight,
+}
+
+impl Connectivity {
+    /// The steps to the neighbours, clockwise starting at north.
+    pub fn deltas(self) -> &'static [PositionDelta] {
+        const FOUR: [PositionDelta; 4] = [
+            PositionDelta { x: 0, y: -1 },
+            PositionDelta { x: 1, y: 0 },
+            PositionDelta { x: 0, y: 1 },
+            PositionDelta { x: -1, y: 0 },
+        ];
+        const EIGHT: [PositionDelta; 8] = [
+            PositionDelta { x: 0, y: -1 },
+            PositionDelta { x: 1, y: -1 },
+            PositionDelta { x: 1, y: 0 },
+            PositionDelta { x: 1, y: 1 },
+            PositionDelta { x: 0, y: 1 },
+            PositionDelta { x: -1, y: 1 },
+            PositionDelta { x: -1, y: 0 },
+            PositionDelta { x: -1, y: -1 },
+        ];
+
+        match self {
+            Connectivity::Four => &FOUR,
+            Connectivity::Eight => &EIGHT,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction8::North.rotate_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.rotate_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::West.turn_right(), Direction8::North);
+        assert_eq!(Direction8::SouthEast.reverse(), Direction8::NorthWest);
+        assert_eq!(Direction8::East.rotate(-11), Direction8::NorthWest);
+        assert_eq!(Direction8::DIAGONALS.map(|d| d.is_diagonal()), [true; 4]);
+
+        for direction in Direction::ALL {
+            assert_eq!(Direction8::from(direction).to_position_delta(), direction.to_position_delta());
+            assert_eq!(Direction8::from(direction.turn_right()), Direction8::from(direction).turn_right());
+        }
+        let deltas: Vec<PositionDelta> = Direction8::ALL.iter().map(|d| d.to_position_delta()).collect();
+        assert_eq!(deltas, Connectivity::Eight.deltas());
+    }
+}
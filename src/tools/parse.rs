@@ -0,0 +1,496 @@
+//! Small parser combinators for puzzle inputs.
+//!
+//! A [`Parser`] consumes the front of a [`Cursor`] and produces a value. Every function taking a
+//! `&mut Cursor` is a parser, and the functions in this module build bigger parsers from smaller
+//! ones:
+//!
+//! ```
+//! use aoc::tools::parse::{key_value, lines, parse_all, separated, spaces, unsigned};
+//!
+//! let equations = lines(key_value(unsigned::<u64>(), separated(unsigned::<u64>(), spaces())));
+//! let parsed = parse_all("190: 10 19\n3267: 81 40 27\n", equations).unwrap();
+//! assert_eq!(parsed, vec![(190, vec![10, 19]), (3267, vec![81, 40, 27])]);
+//! ```
+//!
+//! Failing parsers report what they expected and where, as line and column in the whole input.
+//! Line breaks can be `\n` or `\r\n`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+use crate::error::{Error, Location, ParseError};
+use crate::tools::Matrix;
+
+/// The not yet consumed part of an input.
+#[derive(Debug, Copy, Clone)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Cursor { input, rest: input }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    pub fn location(&self) -> Option<Location> {
+        Location::of(self.input, self.rest)
+    }
+
+    /// An error at the current location.
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(message).at(self.location())
+    }
+
+    /// An error at the current location, naming what was expected and what was found instead.
+    pub fn expected(&self, expected: impl Display) -> ParseError {
+        let found = if self.is_empty() {
+            String::from("end of input")
+        } else if self.at_line_break() {
+            String::from("end of line")
+        } else {
+            let line = self.rest.split('\n').next().unwrap_or_default();
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            format!("'{}'", line.chars().take(20).collect::<String>())
+        };
+        self.error(format!("expected {expected}, found {found}"))
+    }
+
+    /// Whether the rest starts with `\n` or `\r\n`.
+    pub fn at_line_break(&self) -> bool {
+        self.rest.starts_with('\n') || self.rest.starts_with("\r\n")
+    }
+
+    /// Consumes a `\n` or `\r\n` if the rest starts with one.
+    pub fn eat_line_break(&mut self) -> bool {
+        self.eat("\n") || self.eat("\r\n")
+    }
+
+    /// Consumes and returns the longest prefix whose chars all satisfy `predicate`.
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c| !predicate(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    /// Consumes `prefix` if the rest starts with it.
+    pub fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub trait Parser<'a> {
+    type Output;
+
+    fn parse(&self, cursor: &mut Cursor<'a>) -> Result<Self::Output, ParseError>;
+}
+
+impl<'a, T, F> Parser<'a> for F
+where
+    F: Fn(&mut Cursor<'a>) -> Result<T, ParseError>,
+{
+    type Output = T;
+
+    fn parse(&self, cursor: &mut Cursor<'a>) -> Result<T, ParseError> {
+        self(cursor)
+    }
+}
+
+/// Runs `parser` on the whole `input`. Only trailing whitespace may remain afterwards.
+pub fn parse_all<'a, P: Parser<'a>>(input: &'a str, parser: P) -> Result<P::Output, Error> {
+    let mut cursor = Cursor::new(input);
+    let result = parser.parse(&mut cursor)?;
+    cursor.take_while(char::is_whitespace);
+    if !cursor.is_empty() {
+        return Err(cursor.expected("end of input").into());
+    }
+    Ok(result)
+}
+
+/// Matches `literal` exactly.
+pub fn tag<'a>(literal: &'static str) -> impl Parser<'a, Output = ()> {
+    move |cursor: &mut Cursor<'a>| match cursor.eat(literal) {
+        true => Ok(()),
+        false => Err(cursor.expected(format!("'{literal}'"))),
+    }
+}
+
+/// A single `\n` or `\r\n`.
+pub fn line_break<'a>() -> impl Parser<'a, Output = ()> {
+    |cursor: &mut Cursor<'a>| match cursor.eat_line_break() {
+        true => Ok(()),
+        false => Err(cursor.expected("a line break")),
+    }
+}
+
+/// Skips any number of spaces and tabs, but not line breaks.
+pub fn spaces<'a>() -> impl Parser<'a, Output = ()> {
+    |cursor: &mut Cursor<'a>| {
+        cursor.take_while(|c| c == ' ' || c == '\t');
+        Ok(())
+    }
+}
+
+/// A non-empty run of ASCII letters, digits and underscores.
+pub fn word<'a>() -> impl Parser<'a, Output = &'a str> {
+    |cursor: &mut Cursor<'a>| {
+        let word = cursor.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        match word.is_empty() {
+            true => Err(cursor.expected("a word")),
+            false => Ok(word),
+        }
+    }
+}
+
+fn number<'a, T>(cursor: &mut Cursor<'a>, allow_sign: bool) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let start = *cursor;
+    if allow_sign && !cursor.eat("-") {
+        cursor.eat("+");
+    }
+    if cursor.take_while(|c| c.is_ascii_digit()).is_empty() {
+        *cursor = start;
+        return Err(cursor.expected("a number"));
+    }
+
+    let token = &start.rest[..start.rest.len() - cursor.rest.len()];
+    token.parse().map_err(|err| {
+        start
+            .error(format!("invalid number '{token}'"))
+            .caused_by(err)
+    })
+}
+
+/// A number without a sign, like `42`.
+pub fn unsigned<'a, T>() -> impl Parser<'a, Output = T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    |cursor: &mut Cursor<'a>| number(cursor, false)
+}
+
+/// A number with an optional sign, like `-42` or `+42`.
+pub fn signed<'a, T>() -> impl Parser<'a, Output = T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    |cursor: &mut Cursor<'a>| number(cursor, true)
+}
+
+/// One of the given words, producing the value paired with it. Longer words should come first if
+/// one word is a prefix of another.
+pub fn keyword<'a, T: Clone, const N: usize>(options: [(&'static str, T); N]) -> impl Parser<'a, Output = T> {
+    move |cursor: &mut Cursor<'a>| {
+        for (word, value) in options.iter() {
+            if cursor.eat(word) {
+                return Ok(value.clone());
+            }
+        }
+        let words: Vec<String> = options.iter().map(|(word, _)| format!("'{word}'")).collect();
+        Err(cursor.expected(format!("one of {}", words.join(", "))))
+    }
+}
+
+pub fn map<'a, P, F, U>(parser: P, mapper: F) -> impl Parser<'a, Output = U>
+where
+    P: Parser<'a>,
+    F: Fn(P::Output) -> U,
+{
+    move |cursor: &mut Cursor<'a>| parser.parse(cursor).map(&mapper)
+}
+
+/// Like [`map`], but `mapper` can reject the value. The error points to where `parser` started.
+pub fn try_map<'a, P, F, U>(parser: P, mapper: F) -> impl Parser<'a, Output = U>
+where
+    P: Parser<'a>,
+    F: Fn(P::Output) -> Result<U, String>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let start = *cursor;
+        mapper(parser.parse(cursor)?).map_err(|message| start.error(message))
+    }
+}
+
+pub fn pair<'a, A, B>(first: A, second: B) -> impl Parser<'a, Output = (A::Output, B::Output)>
+where
+    A: Parser<'a>,
+    B: Parser<'a>,
+{
+    move |cursor: &mut Cursor<'a>| Ok((first.parse(cursor)?, second.parse(cursor)?))
+}
+
+/// Runs both parsers, keeping the result of the second one.
+pub fn preceded<'a, A, B>(prefix: A, parser: B) -> impl Parser<'a, Output = B::Output>
+where
+    A: Parser<'a>,
+    B: Parser<'a>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        prefix.parse(cursor)?;
+        parser.parse(cursor)
+    }
+}
+
+/// Runs both parsers, keeping the result of the first one.
+pub fn terminated<'a, A, B>(parser: A, suffix: B) -> impl Parser<'a, Output = A::Output>
+where
+    A: Parser<'a>,
+    B: Parser<'a>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let result = parser.parse(cursor)?;
+        suffix.parse(cursor)?;
+        Ok(result)
+    }
+}
+
+pub fn delimited<'a, A, B, C>(prefix: A, parser: B, suffix: C) -> impl Parser<'a, Output = B::Output>
+where
+    A: Parser<'a>,
+    B: Parser<'a>,
+    C: Parser<'a>,
+{
+    terminated(preceded(prefix, parser), suffix)
+}
+
+/// Runs `parser` if it matches, consuming nothing otherwise.
+pub fn optional<'a, P: Parser<'a>>(parser: P) -> impl Parser<'a, Output = Option<P::Output>> {
+    move |cursor: &mut Cursor<'a>| {
+        let start = *cursor;
+        match parser.parse(cursor) {
+            Ok(result) => Ok(Some(result)),
+            Err(_) => {
+                *cursor = start;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Tries `first`, and `second` if that fails.
+pub fn either<'a, A, B>(first: A, second: B) -> impl Parser<'a, Output = A::Output>
+where
+    A: Parser<'a>,
+    B: Parser<'a, Output = A::Output>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let start = *cursor;
+        first.parse(cursor).or_else(|_| {
+            *cursor = start;
+            second.parse(cursor)
+        })
+    }
+}
+
+/// One or more `item`s with a `separator` in between. Once a separator with more than whitespace
+/// matched, or an item started to match, the item must match completely.
+pub fn separated<'a, P, S>(item: P, separator: S) -> impl Parser<'a, Output = Vec<P::Output>>
+where
+    P: Parser<'a>,
+    S: Parser<'a>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let mut result = vec![item.parse(cursor)?];
+        loop {
+            let start = *cursor;
+            if separator.parse(cursor).is_err() {
+                *cursor = start;
+                return Ok(result);
+            }
+
+            let skipped = &start.rest[..start.rest.len() - cursor.rest.len()];
+            let before_item = cursor.rest.len();
+            match item.parse(cursor) {
+                Ok(next) => result.push(next),
+                // e.g. trailing spaces, which don't mean that another item follows
+                Err(_) if skipped.trim().is_empty() && cursor.rest.len() == before_item => {
+                    *cursor = start;
+                    return Ok(result);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// A `key: value` pair, with optional spaces after the colon.
+pub fn key_value<'a, K, V>(key: K, value: V) -> impl Parser<'a, Output = (K::Output, V::Output)>
+where
+    K: Parser<'a>,
+    V: Parser<'a>,
+{
+    pair(terminated(key, pair(tag(":"), spaces())), value)
+}
+
+/// Runs `item` on each line until the end of the input or a blank line.
+pub fn lines<'a, P: Parser<'a>>(item: P) -> impl Parser<'a, Output = Vec<P::Output>> {
+    move |cursor: &mut Cursor<'a>| {
+        let mut result = vec![item.parse(cursor)?];
+        loop {
+            // Another line follows unless the input ends or a blank line comes next
+            let mut next = *cursor;
+            if !next.eat_line_break() || next.is_empty() || next.at_line_break() {
+                break;
+            }
+            *cursor = next;
+            result.push(item.parse(cursor)?);
+        }
+        if !cursor.is_empty() && !cursor.at_line_break() {
+            return Err(cursor.expected("end of line"));
+        }
+        Ok(result)
+    }
+}
+
+/// Runs `item` on each section of lines, where sections are separated by blank lines.
+pub fn sections<'a, P: Parser<'a>>(item: P) -> impl Parser<'a, Output = Vec<P::Output>> {
+    move |cursor: &mut Cursor<'a>| {
+        let mut result = vec![item.parse(cursor)?];
+        loop {
+            let mut next = *cursor;
+            if !(next.eat_line_break() && next.at_line_break()) {
+                break;
+            }
+            *cursor = next;
+            while cursor.eat_line_break() {}
+            if cursor.is_empty() {
+                break;
+            }
+            result.push(item.parse(cursor)?);
+        }
+        Ok(result)
+    }
+}
+
+/// A rectangle of chars up to the end of the input or a blank line, each converted by `cell`.
+pub fn grid<'a, T, F>(cell: F) -> impl Parser<'a, Output = Matrix<T>>
+where
+    F: Fn(char) -> Option<T>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let rows = lines(|cursor: &mut Cursor<'a>| {
+            let start = *cursor;
+            let row = cursor.take_while(|c| c != '\n' && c != '\r');
+            row.char_indices()
+                .map(|(idx, char)| {
+                    cell(char).ok_or_else(|| {
+                        let location = Location::of(start.input, &row[idx..]);
+                        ParseError::new(format!("unexpected '{char}' in grid")).at(location)
+                    })
+                })
+                .collect::<Result<Vec<T>, _>>()
+                .map(|cells| (start, cells))
+        })
+        .parse(cursor)?;
+
+        let width = rows[0].1.len();
+        if let Some((start, _)) = rows.iter().find(|(_, cells)| cells.len() != width) {
+            return Err(start.error(format!("expected a row of width {width}")));
+        }
+
+        let height = rows.len();
+        let data = rows.into_iter().flat_map(|(_, cells)| cells).collect();
+        Ok(Matrix::from_vec(width, height, data).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    enum Colour {
+        Red,
+        Green,
+        Blue,
+    }
+
+    fn error_at<T>(result: Result<T, Error>) -> (String, Option<Location>) {
+        match result {
+            Err(Error::Parse(error)) => (error.message, error.location),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_combinators() {
+        let draw = separated(
+            pair(terminated(unsigned::<u32>(), spaces()), keyword([("red", Colour::Red), ("green", Colour::Green), ("blue", Colour::Blue)])),
+            tag(", "),
+        );
+        let game = key_value(preceded(tag("Game "), unsigned::<u32>()), separated(draw, tag("; ")));
+        let input = "Game 1: 3 blue, 4 red; 2 green\nGame 2: 1 red\n";
+        let games = parse_all(input, lines(game)).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].0, 1);
+        assert_eq!(games[0].1, vec![vec![(3, Colour::Blue), (4, Colour::Red)], vec![(2, Colour::Green)]]);
+        assert_eq!(games[1], (2, vec![vec![(1, Colour::Red)]]));
+
+        assert_eq!(parse_all("-12, +3, 4", separated(signed::<i32>(), tag(", "))).unwrap(), vec![-12, 3, 4]);
+        assert_eq!(parse_all("abc", optional(unsigned::<u8>())).map_err(|e| e.to_string()).unwrap_err(),
+                   "could not parse input: expected end of input, found 'abc' at line 1, column 1");
+        assert_eq!(parse_all("x", either(map(unsigned::<u8>(), |_| "number"), word())).unwrap(), "x");
+        assert_eq!(parse_all("AAA = (BBB, CCC)", pair(word(), preceded(tag(" = "), delimited(tag("("), separated(word(), tag(", ")), tag(")"))))).unwrap(),
+                   ("AAA", vec!["BBB", "CCC"]));
+    }
+
+    #[test]
+    fn test_sections_and_grid() {
+        let input = "#.\n.#\n\n1|2\n3|4\n\n\n";
+        let (grid_section, rules) = parse_all(input, pair(terminated(grid(|c| Some(c == '#')), tag("\n\n")), lines(separated(unsigned::<u8>(), tag("|"))))).unwrap();
+        assert_eq!(grid_section.get(0, 0), Some(&true));
+        assert_eq!(grid_section.get(0, 1), Some(&false));
+        assert_eq!(rules, vec![vec![1, 2], vec![3, 4]]);
+
+        let blocks = parse_all("1\n2\n\n3\n", sections(lines(unsigned::<u8>()))).unwrap();
+        assert_eq!(blocks, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_error_locations() {
+        let numbers = || lines(separated(unsigned::<u8>(), spaces()));
+        assert_eq!(error_at(parse_all("1 2\n3 x 4", numbers())), (String::from("expected end of line, found ' x 4'"), Some(Location::new(2, 2))));
+        assert_eq!(error_at(parse_all("1 2\n3 300", numbers())), (String::from("invalid number '300'"), Some(Location::new(2, 3))));
+        assert_eq!(error_at(parse_all("a: b", key_value(word(), unsigned::<u8>()))), (String::from("expected a number, found 'b'"), Some(Location::new(1, 4))));
+        assert_eq!(error_at(parse_all("1,2,", separated(unsigned::<u8>(), tag(",")))), (String::from("expected a number, found end of input"), Some(Location::new(1, 5))));
+        assert_eq!(error_at(parse_all("#.\n#", grid(|c| Some(c == '#')))), (String::from("expected a row of width 2"), Some(Location::new(2, 1))));
+        assert_eq!(error_at(parse_all("#.\n#x", grid(|c| (c != 'x').then_some(c)))), (String::from("unexpected 'x' in grid"), Some(Location::new(2, 2))));
+        assert_eq!(error_at(parse_all("1\r2", numbers())), (String::from("expected end of line, found '\r2'"), Some(Location::new(1, 2))));
+    }
+
+    #[test]
+    fn test_crlf() {
+        let numbers = || lines(separated(unsigned::<u8>(), spaces()));
+        assert_eq!(parse_all("1 2\r\n3\r\n", numbers()).unwrap(), vec![vec![1, 2], vec![3]]);
+        assert_eq!(error_at(parse_all("1 2\r\n3 x\r\n", numbers())), (String::from("expected end of line, found ' x'"), Some(Location::new(2, 2))));
+
+        let blocks = parse_all("1\r\n2\r\n\r\n\r\n3\r\n", sections(lines(unsigned::<u8>()))).unwrap();
+        assert_eq!(blocks, vec![vec![1, 2], vec![3]]);
+
+        let input = "#.\r\n.#\r\n\r\n1|2\r\n";
+        let blank_line = pair(line_break(), line_break());
+        let (grid, rules) = parse_all(input, pair(terminated(grid(|c| Some(c == '#')), blank_line), lines(separated(unsigned::<u8>(), tag("|"))))).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.get(1, 1), Some(&true));
+        assert_eq!(rules, vec![vec![1, 2]]);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use crate::tools::{Direction, Direction8, Position, PositionDelta};
+
+/// A point in unbounded, signed 2D space. Like [`Position`], `y` grows downwards, so north is
+/// `(0, -1)`. Doubles as a vector between points.
+#[derive(Debug, Default, Hash, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance if diagonal steps are allowed, like a king in chess.
+    pub fn chebyshev(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Rotates by 90° clockwise around the origin.
+    pub fn rotate_right(self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotates by 90° counter-clockwise around the origin.
+    pub fn rotate_left(self) -> Point {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Rotates by `quarter_turns` times 90° clockwise around the origin; negative values rotate
+    /// counter-clockwise.
+    pub fn rotate(self, quarter_turns: i32) -> Point {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => self.rotate_right(),
+            2 => -self,
+            _ => self.rotate_left(),
+        }
+    }
+
+    /// Each coordinate reduced to -1, 0 or 1, e.g. to walk a straight or diagonal line one step
+    /// at a time.
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    /// The matching matrix position, or `None` if a coordinate is negative.
+    pub fn to_position(self) -> Option<Position> {
+        Some(Position {
+            x: self.x.try_into().ok()?,
+            y: self.y.try_into().ok()?,
+        })
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(x={}, y={})", self.x, self.y)
+    }
+}
+
+impl From<Position> for Point {
+    fn from(position: Position) -> Self {
+        Point::new(position.x as i64, position.y as i64)
+    }
+}
+
+impl From<&Position> for Point {
+    fn from(position: &Position) -> Self {
+        Point::new(position.x as i64, position.y as i64)
+    }
+}
+
+impl From<PositionDelta> for Point {
+    fn from(delta: PositionDelta) -> Self {
+        Point::new(delta.x as i64, delta.y as i64)
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.to_position_delta().into()
+    }
+}
+
+impl From<Direction8> for Point {
+    fn from(direction: Direction8) -> Self {
+        direction.to_position_delta().into()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<i64> for Point {
+    fn mul_assign(&mut self, factor: i64) {
+        *self = *self * factor;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let a = Point::new(3, -4);
+        let b = Point::new(-1, 2);
+        assert_eq!(a + b, Point::new(2, -2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(a * 3, Point::new(9, -12));
+        assert_eq!(-a, Point::new(-3, 4));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(a.manhattan(a), 0);
+        assert_eq!((a - b).signum(), Point::new(1, -1));
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        c *= 2;
+        assert_eq!(c, b * 2);
+
+        // rotating a direction matches turning it
+        for direction in Direction8::ALL {
+            assert_eq!(Point::from(direction).rotate_right(), Point::from(direction.turn_right()));
+            assert_eq!(Point::from(direction).rotate_left(), Point::from(direction.turn_left()));
+        }
+        for turns in -8..8 {
+            assert_eq!(a.rotate(turns).rotate(-turns), a);
+            assert_eq!(a.rotate(turns).manhattan(Point::ORIGIN), a.manhattan(Point::ORIGIN));
+        }
+        assert_eq!(a.rotate(2), -a);
+
+        assert_eq!(Point::new(2, 5).to_position(), Some(Position { x: 2, y: 5 }));
+        assert_eq!(Point::new(2, -1).to_position(), None);
+        assert_eq!(Point::from(Position { x: 7, y: 1 }), Point::new(7, 1));
+        assert_eq!(Point::from(PositionDelta::new(-2, 3)), Point::new(-2, 3));
+    }
+}
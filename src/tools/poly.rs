@@ -0,0 +1,120 @@
+//! Fitting polynomials through sampled values and extrapolating them, e.g. for sequences that
+//! grow quadratically.
+//!
+//! ```
+//! use aoc::tools::poly::Polynomial;
+//! use aoc::tools::Rational;
+//!
+//! // 1, 3, 6, 10, ... are the triangular numbers (x + 1)(x + 2) / 2
+//! let triangular = Polynomial::fit_sequence([1, 3, 6, 10]);
+//! assert_eq!(triangular.degree(), Some(2));
+//! assert_eq!(triangular.eval(99), Rational::from(5050));
+//! assert_eq!(triangular.eval(-1), Rational::ZERO);
+//! ```
+
+use crate::tools::Rational;
+
+/// A polynomial with rational coefficients, stored in Newton form,
+/// `c0 + c1 (x - x0) + c2 (x - x0)(x - x1) + ...`, which can be evaluated without the large
+/// intermediate values the usual coefficients would need.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polynomial {
+    nodes: Vec<Rational>,
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    /// The polynomial of minimal degree through all `points`, or `None` if two points share an
+    /// `x` value.
+    pub fn interpolate<X, Y>(points: impl IntoIterator<Item = (X, Y)>) -> Option<Polynomial>
+    where
+        X: Into<Rational>,
+        Y: Into<Rational>,
+    {
+        let (nodes, mut coefficients): (Vec<Rational>, Vec<Rational>) =
+            points.into_iter().map(|(x, y)| (x.into(), y.into())).unzip();
+
+        // Divided differences, in place: after round `j`, `coefficients[i]` for `i >= j` is the
+        // difference of the points `i - j ..= i`.
+        for j in 1..nodes.len() {
+            for i in (j..nodes.len()).rev() {
+                let dx = nodes[i] - nodes[i - j];
+                if dx.is_zero() {
+                    return None;
+                }
+                coefficients[i] = (coefficients[i] - coefficients[i - 1]) / dx;
+            }
+        }
+
+        let degree = coefficients.iter().rposition(|c| !c.is_zero());
+        let len = degree.map_or(0, |degree| degree + 1);
+        let mut polynomial = Polynomial { nodes, coefficients };
+        polynomial.nodes.truncate(len);
+        polynomial.coefficients.truncate(len);
+        Some(polynomial)
+    }
+
+    /// The polynomial of minimal degree with `values[x]` at `x = 0, 1, 2, ...`.
+    pub fn fit_sequence<T: Into<Rational>>(values: impl IntoIterator<Item = T>) -> Polynomial {
+        Polynomial::interpolate(values.into_iter().enumerate()).expect("sequence indices are distinct")
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn eval(&self, x: impl Into<Rational>) -> Rational {
+        let x = x.into();
+        self.coefficients
+            .iter()
+            .zip(self.nodes.iter())
+            .rev()
+            .fold(Rational::ZERO, |acc, (coefficient, node)| acc * (x - *node) + *coefficient)
+    }
+
+    /// The usual coefficients, starting with the constant one, so `[c, b, a]` for
+    /// `a x² + b x + c`. Empty for the zero polynomial.
+    pub fn coefficients(&self) -> Vec<Rational> {
+        let mut result: Vec<Rational> = Vec::with_capacity(self.coefficients.len());
+        for (coefficient, node) in self.coefficients.iter().zip(self.nodes.iter()).rev() {
+            // result = result * (x - node) + coefficient
+            result.insert(0, Rational::ZERO);
+            for i in 0..result.len() - 1 {
+                result[i] = result[i] - result[i + 1] * *node;
+            }
+            result[0] = result[0] + *coefficient;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_polynomial() {
+        let linear = Polynomial::fit_sequence([0, 3, 6, 9, 12, 15]);
+        assert_eq!(linear.degree(), Some(1));
+        assert_eq!(linear.eval(6), Rational::from(18));
+        assert_eq!(linear.eval(-1), Rational::from(-3));
+        assert_eq!(linear.coefficients(), vec![Rational::ZERO, Rational::from(3)]);
+
+        let cubic = Polynomial::fit_sequence([10, 13, 16, 21, 30, 45]);
+        assert_eq!(cubic.degree(), Some(3));
+        assert_eq!(cubic.eval(6), Rational::from(68));
+        assert_eq!(cubic.eval(-1), Rational::from(5));
+
+        // x²/2 - 1/2 through unevenly spaced points
+        let half = Rational::new(1, 2);
+        let parabola = Polynomial::interpolate([(3, 4), (-1, 0), (5, 12)]).unwrap();
+        assert_eq!(parabola.coefficients(), vec![-half, Rational::ZERO, half]);
+        assert_eq!(parabola.eval(half), Rational::new(-3, 8));
+
+        assert_eq!(Polynomial::fit_sequence([7, 7, 7]).coefficients(), vec![Rational::from(7)]);
+        assert_eq!(Polynomial::fit_sequence([0, 0]).degree(), None);
+        assert_eq!(Polynomial::fit_sequence(Vec::<i64>::new()).eval(3), Rational::ZERO);
+        assert_eq!(Polynomial::interpolate([(1, 2), (1, 3)]), None);
+    }
+}
@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use crate::tools::math::gcd;
+
+/// An exact fraction of two `i128`, always in lowest terms with a positive denominator.
+///
+/// Arithmetic panics on overflow instead of silently wrapping, even in release builds.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numerator: 0, denominator: 1 };
+    pub const ONE: Rational = Rational { numerator: 1, denominator: 1 };
+
+    /// Panics if `denominator` is 0.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert_ne!(denominator, 0, "rational with a zero denominator");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Rational {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as an integer, or `None` if it is a proper fraction.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    pub fn abs(self) -> Self {
+        Rational {
+            numerator: checked(self.numerator.checked_abs()),
+            denominator: self.denominator,
+        }
+    }
+
+    /// `1 / self`. Panics for 0.
+    pub fn recip(self) -> Self {
+        Rational::new(self.denominator, self.numerator)
+    }
+
+    /// The largest integer not greater than the value.
+    pub fn floor(self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+}
+
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("rational arithmetic overflowed")
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+macro_rules! rational_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational {
+                fn from(value: $t) -> Self {
+                    Rational { numerator: value as i128, denominator: 1 }
+                }
+            }
+        )*
+    };
+}
+
+rational_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational {
+        // Scaling to the lcm of the denominators keeps the intermediate values small
+        let divisor = gcd(self.denominator, other.denominator);
+        let (a, b) = (self.denominator / divisor, other.denominator / divisor);
+        let numerator = checked(
+            checked(self.numerator.checked_mul(b)).checked_add(checked(other.numerator.checked_mul(a))),
+        );
+        Rational::new(numerator, checked(self.denominator.checked_mul(b)))
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numerator: checked(self.numerator.checked_neg()),
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational {
+        // Cancelling crosswise first keeps the intermediate values small
+        let a = gcd(self.numerator, other.denominator);
+        let b = gcd(other.numerator, self.denominator);
+        Rational::new(
+            checked((self.numerator / a).checked_mul(other.numerator / b)),
+            checked((self.denominator / b).checked_mul(other.denominator / a)),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// Panics when dividing by 0.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Rational) -> Rational {
+        self * other.recip()
+    }
+}
+
+impl Ord for Rational {
+    /// Compares the integer parts first, then the reciprocals of the fractional parts, like
+    /// continued fractions, so no products are needed that could overflow.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut a, mut b) = (self.numerator, self.denominator);
+        let (mut c, mut d) = (other.numerator, other.denominator);
+        let mut reversed = false;
+        loop {
+            let ordering = a.div_euclid(b).cmp(&c.div_euclid(d));
+            let (r1, r2) = (a.rem_euclid(b), c.rem_euclid(d));
+            let ordering = match (ordering, r1, r2) {
+                (Ordering::Equal, 0, 0) => Ordering::Equal,
+                (Ordering::Equal, 0, _) => Ordering::Less,
+                (Ordering::Equal, _, 0) => Ordering::Greater,
+                // r1/b < r2/d <=> b/r1 > d/r2
+                (Ordering::Equal, _, _) => {
+                    (a, b, c, d) = (b, r1, d, r2);
+                    reversed = !reversed;
+                    continue;
+                }
+                (ordering, _, _) => ordering,
+            };
+            return if reversed { ordering.reverse() } else { ordering };
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(1, 2);
+        let third = Rational::new(-2, -6);
+        assert_eq!(Rational::new(3, -6), -half);
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!((half + half).to_integer(), Some(1));
+        assert_eq!(third.to_integer(), None);
+        assert_eq!(Rational::new(-7, 2).floor(), -4);
+        assert_eq!(Rational::new(0, -5), Rational::ZERO);
+        assert!(third < half && -half < third);
+        assert_eq!(Rational::new(-6, 4).to_string(), "-3/2");
+        assert_eq!(Rational::from(7u8).to_string(), "7");
+
+        // large values that only fit because of the cancelling
+        let big = Rational::new(i128::MAX, 3);
+        assert_eq!(big * Rational::new(3, i128::MAX), Rational::ONE);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let max = Rational::from(i128::MAX);
+        assert!(max > Rational::from(-i128::MAX));
+        assert!(Rational::from(i128::MIN) < -max);
+        assert!(Rational::new(i128::MAX - 1, i128::MAX) > Rational::new(i128::MAX - 2, i128::MAX - 1));
+        assert!(Rational::new(-1, i128::MAX) > Rational::new(-1, i128::MAX - 1));
+        assert_eq!(Rational::new(-i128::MAX, 3).cmp(&Rational::new(-i128::MAX, 3)), Ordering::Equal);
+
+        let mut values: Vec<Rational> = [(3, 4), (-7, 2), (0, 1), (-1, 3), (5, 7), (-i128::MAX, 3), (i128::MAX, 2), (-1, 2)]
+            .into_iter()
+            .map(|(n, d)| Rational::new(n, d))
+            .collect();
+        values.sort();
+        let floats: Vec<f64> = values.iter().map(|v| v.numerator() as f64 / v.denominator() as f64).collect();
+        assert!(floats.windows(2).all(|pair| pair[0] < pair[1]), "{values:?}");
+    }
+
+    #[test]
+    #[should_panic(expected = "rational arithmetic overflowed")]
+    fn test_negate_min() {
+        let _ = -Rational::from(i128::MIN);
+    }
+}
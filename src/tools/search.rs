@@ -0,0 +1,373 @@
+//! Graph searches over implicit graphs.
+//!
+//! A graph is given as a start node and a neighbour function; nodes can be anything hashable,
+//! e.g. a [`Position`] or a `(Position, Direction)` state. [`grid_neighbours`] and [`grid_edges`]
+//! turn a [`Matrix`] into such a neighbour function.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+use crate::tools::{Connectivity, FastMap, Matrix, Position};
+
+/// Edge weights. `Default` must be zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C> Cost for C where C: Copy + Ord + Add<Output = C> + Default {}
+
+/// The nodes reached by a search, with their cost and the node they were reached from.
+#[derive(Debug, Clone)]
+pub struct Visited<N, C> {
+    costs: FastMap<N, C>,
+    parents: FastMap<N, N>,
+    /// The first goal node that was reached, if any.
+    pub goal: Option<N>,
+}
+
+impl<N, C> Visited<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn new(start: N) -> Self
+    where
+        C: Default,
+    {
+        Visited {
+            costs: FastMap::from_iter([(start, C::default())]),
+            parents: FastMap::default(),
+            goal: None,
+        }
+    }
+
+    /// The cost to reach `node`, or `None` if it was not reached.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// The cost to reach the goal.
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(self.goal.as_ref()?)
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (&N, C)> {
+        self.costs.iter().map(|(node, cost)| (node, *cost))
+    }
+
+    /// The path from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.contains(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path from the start to the goal, both included.
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+/// Breadth-first search, the cost is the number of steps. Stops at the first node for which
+/// `is_goal` returns true; pass `|_| false` to explore everything reachable.
+pub fn bfs<N, F, I, G>(start: N, mut neighbours: F, mut is_goal: G) -> Visited<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut visited = Visited::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        if is_goal(&node) {
+            visited.goal = Some(node);
+            break;
+        }
+
+        for next in neighbours(&node) {
+            if !visited.contains(&next) {
+                visited.costs.insert(next.clone(), steps + 1);
+                visited.parents.insert(next.clone(), node.clone());
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    visited
+}
+
+/// Depth-first search. The cost is the depth in the search tree, which is not necessarily the
+/// shortest distance.
+pub fn dfs<N, F, I, G>(start: N, mut neighbours: F, mut is_goal: G) -> Visited<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut visited = Visited::new(start.clone());
+    let mut stack = vec![(start, 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        if is_goal(&node) {
+            visited.goal = Some(node);
+            break;
+        }
+
+        for next in neighbours(&node) {
+            if !visited.contains(&next) {
+                visited.costs.insert(next.clone(), depth + 1);
+                visited.parents.insert(next.clone(), node.clone());
+                stack.push((next, depth + 1));
+            }
+        }
+    }
+
+    visited
+}
+
+/// Heap entry ordered by the estimated total cost only.
+struct Queued<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimate.cmp(&other.estimate)
+    }
+}
+
+/// Cheapest paths for non-negative edge costs. Stops once the cheapest goal node is settled.
+pub fn dijkstra<N, C, F, I, G>(start: N, neighbours: F, is_goal: G) -> Visited<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    G: FnMut(&N) -> bool,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` estimates the remaining cost to a goal and must never overestimate it,
+/// otherwise the returned path is not guaranteed to be the cheapest.
+pub fn astar<N, C, F, I, H, G>(start: N, mut neighbours: F, mut heuristic: H, mut is_goal: G) -> Visited<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut visited = Visited::new(start.clone());
+    let mut queue = BinaryHeap::from([Reverse(Queued {
+        estimate: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    })]);
+
+    while let Some(Reverse(Queued { cost, node, .. })) = queue.pop() {
+        if visited.cost(&node).is_some_and(|best| best < cost) {
+            // outdated entry, the node was reached cheaper in the meantime
+            continue;
+        }
+        if is_goal(&node) {
+            visited.goal = Some(node);
+            break;
+        }
+
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            if visited.cost(&next).is_none_or(|best| next_cost < best) {
+                visited.costs.insert(next.clone(), next_cost);
+                visited.parents.insert(next.clone(), node.clone());
+                queue.push(Reverse(Queued {
+                    estimate: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                }));
+            }
+        }
+    }
+
+    visited
+}
+
+/// Counts the distinct paths from `start` to any goal node. Paths end at the first goal node
+/// they reach. The graph must not contain cycles reachable from `start`.
+pub fn count_paths<N, F, I, G>(start: N, mut neighbours: F, mut is_goal: G) -> u64
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    fn count<N, F, I, G>(node: N, neighbours: &mut F, is_goal: &mut G, cache: &mut FastMap<N, u64>) -> u64
+    where
+        N: Clone + Eq + Hash,
+        F: FnMut(&N) -> I,
+        I: IntoIterator<Item = N>,
+        G: FnMut(&N) -> bool,
+    {
+        if is_goal(&node) {
+            return 1;
+        }
+        if let Some(paths) = cache.get(&node) {
+            return *paths;
+        }
+
+        let paths = neighbours(&node)
+            .into_iter()
+            .map(|next| count(next, neighbours, is_goal, cache))
+            .sum();
+        cache.insert(node, paths);
+        paths
+    }
+
+    count(start, &mut neighbours, &mut is_goal, &mut FastMap::default())
+}
+
+/// Neighbour function for walking a matrix. `can_step(from, to)` decides whether a step between
+/// two neighbouring cells is possible, e.g. `|_, to| *to != '#'`.
+pub fn grid_neighbours<'a, T, P>(
+    matrix: &'a Matrix<T>,
+    connectivity: Connectivity,
+    mut can_step: P,
+) -> impl FnMut(&Position) -> Vec<Position> + 'a
+where
+    P: FnMut(&T, &T) -> bool + 'a,
+{
+    move |position| match matrix.get_position(position) {
+        Some(from) => matrix
+            .neighbours(position, connectivity)
+            .filter(|(_, to)| can_step(from, to))
+            .map(|(next, _)| next)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Weighted neighbour function for walking a matrix. `step_cost(from, to)` returns the cost of a
+/// step, or `None` if it is impossible.
+pub fn grid_edges<'a, T, C, P>(
+    matrix: &'a Matrix<T>,
+    connectivity: Connectivity,
+    mut step_cost: P,
+) -> impl FnMut(&Position) -> Vec<(Position, C)> + 'a
+where
+    P: FnMut(&T, &T) -> Option<C> + 'a,
+{
+    move |position| match matrix.get_position(position) {
+        Some(from) => matrix
+            .neighbours(position, connectivity)
+            .filter_map(|(next, to)| Some((next, step_cost(from, to)?)))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// The Manhattan distance, an admissible A* heuristic for 4-connected grids with step costs ≥ 1.
+pub fn manhattan(a: &Position, b: &Position) -> usize {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.####.#.
+......#E";
+
+    fn find(matrix: &Matrix<char>, c: char) -> Position {
+        matrix.find_first(&c).unwrap()
+    }
+
+    #[test]
+    fn test_grid_searches() {
+        let matrix = Matrix::char_matrix_from_string(MAZE).unwrap();
+        let (start, end) = (find(&matrix, 'S'), find(&matrix, 'E'));
+        let is_end = |pos: &Position| *pos == end;
+
+        let visited = bfs(start.clone(), grid_neighbours(&matrix, Connectivity::Four, |_, to| *to != '#'), is_end);
+        assert_eq!(visited.goal_cost(), Some(15));
+        let path = visited.goal_path().unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!((path.first(), path.last()), (Some(&start), Some(&end)));
+        assert!(path.windows(2).all(|step| manhattan(&step[0], &step[1]) == 1));
+        assert!(path.iter().all(|pos| matrix.get_position(pos) != Some(&'#')));
+
+        let everything = bfs(start.clone(), grid_neighbours(&matrix, Connectivity::Four, |_, to| *to != '#'), |_| false);
+        assert_eq!(everything.len(), MAZE.chars().filter(|c| ".SE".contains(*c)).count());
+        assert_eq!(everything.goal, None);
+
+        let found = dfs(start.clone(), grid_neighbours(&matrix, Connectivity::Four, |_, to| *to != '#'), is_end);
+        assert!(found.goal_cost().unwrap() >= 15);
+
+        // walking over '.' costs 1, the walls can be broken through for 10
+        let edges = || grid_edges(&matrix, Connectivity::Four, |_, to| Some(if *to == '#' { 10 } else { 1 }));
+        let cheapest = dijkstra(start.clone(), edges(), is_end);
+        assert_eq!(cheapest.goal_cost(), Some(15));
+        let estimated = astar(start.clone(), edges(), |pos| manhattan(pos, &end), is_end);
+        assert_eq!(estimated.goal_cost(), Some(15));
+        assert!(estimated.len() <= cheapest.len());
+
+        let walls_only = grid_edges(&matrix, Connectivity::Four, |_, to| (*to != '.').then_some(1));
+        assert_eq!(dijkstra(start, walls_only, is_end).goal, None);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        // lattice paths through a 3x3 grid of cells
+        let matrix = Matrix::new(4, 4, ());
+        let right_or_down = |pos: &Position| {
+            [Position { x: pos.x + 1, y: pos.y }, Position { x: pos.x, y: pos.y + 1 }]
+                .into_iter()
+                .filter(|next| matrix.validate_position(next))
+                .collect::<Vec<_>>()
+        };
+        let end = Position { x: 3, y: 3 };
+        assert_eq!(count_paths(Position { x: 0, y: 0 }, right_or_down, |pos| *pos == end), 20);
+        assert_eq!(count_paths(0u32, |n| [n + 1, n + 2].into_iter().filter(|n| *n <= 10), |n| *n == 10), 89);
+    }
+}
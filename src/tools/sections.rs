@@ -0,0 +1,242 @@
+//! Inputs made of blank-line separated sections, and typed readers for them.
+//!
+//! A day declares the shape of its input as a type and lets [`read_sections`] do the splitting:
+//!
+//! ```
+//! use aoc::error::Error;
+//! use aoc::tools::sections::{read_sections, FromLine};
+//!
+//! struct Rule(u32, u32);
+//!
+//! impl FromLine for Rule {
+//!     fn from_line(line: &str) -> Result<Self, Error> {
+//!         let (a, b) = line.split_once('|').ok_or(Error::parse("expected a rule like 'a|b'"))?;
+//!         Ok(Rule(aoc::tools::parse_token(line, a)?, aoc::tools::parse_token(line, b)?))
+//!     }
+//! }
+//!
+//! let (rules, jobs): (Vec<Rule>, Vec<Vec<u32>>) = read_sections("1|2\n2|3\n\n1,2,3\n").unwrap();
+//! assert_eq!(rules.len(), 2);
+//! assert_eq!(jobs, vec![vec![1, 2, 3]]);
+//! ```
+//!
+//! Errors are reported relative to the whole input.
+
+use std::str::FromStr;
+use crate::error::{Error, Location};
+use crate::tools::parse_token;
+
+/// Splits `input` at blank lines. Handles `\r\n` line endings, and ignores leading, trailing and
+/// repeated blank lines. The sections are subslices of `input` without their final line break.
+pub fn sections(input: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if let Some(start) = start.take() {
+                result.push(&input[start..end]);
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        result.push(&input[start..end]);
+    }
+
+    result
+}
+
+/// Makes an error reported for `part` relative to `input`. Errors without a location point to the
+/// start of `part`.
+fn relative_to(error: Error, input: &str, part: &str) -> Error {
+    match error {
+        Error::Parse(mut error) if error.location.is_none() => {
+            error.location = Location::of(input, part);
+            Error::Parse(error)
+        }
+        other => other.within(input, part),
+    }
+}
+
+/// A value parsed from a single line.
+pub trait FromLine: Sized {
+    fn from_line(line: &str) -> Result<Self, Error>;
+}
+
+/// A list of values separated by commas and/or whitespace, like `1,2,3` or `1 2 3`.
+impl<T> FromLine for Vec<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    fn from_line(line: &str) -> Result<Self, Error> {
+        line.split([',', ' ', '\t'])
+            .filter(|token| !token.is_empty())
+            .map(|token| parse_token(line, token))
+            .collect()
+    }
+}
+
+macro_rules! from_line_via_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl FromLine for $t {
+                fn from_line(line: &str) -> Result<Self, Error> {
+                    parse_token(line, line.trim())
+                }
+            }
+        )*
+    };
+}
+
+from_line_via_from_str!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, String);
+
+/// A value parsed from a single section.
+pub trait FromSection: Sized {
+    fn from_section(section: &str) -> Result<Self, Error>;
+}
+
+/// One value per line.
+impl<T: FromLine> FromSection for Vec<T> {
+    fn from_section(section: &str) -> Result<Self, Error> {
+        section
+            .lines()
+            .map(|line| T::from_line(line).map_err(|error| relative_to(error, section, line)))
+            .collect()
+    }
+}
+
+/// The sections of an input that were not read yet.
+#[derive(Debug)]
+pub struct Sections<'a> {
+    input: &'a str,
+    sections: std::vec::IntoIter<&'a str>,
+}
+
+impl<'a> Sections<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Sections {
+            input,
+            sections: sections(input).into_iter(),
+        }
+    }
+
+    /// Parses the next section.
+    pub fn read<T: FromSection>(&mut self) -> Result<T, Error> {
+        let section = self
+            .sections
+            .next()
+            .ok_or_else(|| Error::parse("expected another section after a blank line"))?;
+        T::from_section(section).map_err(|error| relative_to(error, self.input, section))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.len() == 0
+    }
+
+    /// Fails if there are sections left.
+    pub fn finish(mut self) -> Result<(), Error> {
+        match self.sections.next() {
+            Some(section) => Err(Error::parse_at(self.input, section, "unexpected section")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A value read from one or more sections. Every [`FromSection`] reads a single section, and
+/// tuples read one section per element, in order.
+pub trait FromSections: Sized {
+    fn from_sections(sections: &mut Sections) -> Result<Self, Error>;
+}
+
+impl<T: FromSection> FromSections for T {
+    fn from_sections(sections: &mut Sections) -> Result<Self, Error> {
+        sections.read()
+    }
+}
+
+/// All remaining sections, e.g. an unknown number of maps after a header section.
+#[derive(Debug, Clone)]
+pub struct Repeated<T>(pub Vec<T>);
+
+impl<T: FromSection> FromSections for Repeated<T> {
+    fn from_sections(sections: &mut Sections) -> Result<Self, Error> {
+        let mut result = Vec::new();
+        while !sections.is_empty() {
+            result.push(sections.read()?);
+        }
+        Ok(Repeated(result))
+    }
+}
+
+macro_rules! from_sections_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: FromSections),+> FromSections for ($($name,)+) {
+            fn from_sections(sections: &mut Sections) -> Result<Self, Error> {
+                Ok(($($name::from_sections(sections)?,)+))
+            }
+        }
+    };
+}
+
+from_sections_for_tuple!(A, B);
+from_sections_for_tuple!(A, B, C);
+from_sections_for_tuple!(A, B, C, D);
+
+/// Reads the whole `input` as a `T`. Fails if sections are missing or left over.
+pub fn read_sections<T: FromSections>(input: &str) -> Result<T, Error> {
+    let mut sections = Sections::new(input);
+    let result = T::from_sections(&mut sections)?;
+    sections.finish()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn location<T>(result: Result<T, Error>) -> Option<Location> {
+        match result {
+            Err(Error::Parse(error)) => error.location,
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(sections("a\nb\n\nc\n"), vec!["a\nb", "c"]);
+        assert_eq!(sections("\r\na\r\nb\r\n\r\n\r\n\r\nc\r\n\r\n"), vec!["a\r\nb", "c"]);
+        assert_eq!(sections("a\n  \nc"), vec!["a", "c"]);
+        assert_eq!(sections("\n\n"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_read_sections() {
+        let input = "seeds 1\n\n1 2\n3 4\n\n5\n\n6\n";
+        let (header, numbers, Repeated(rest)): (Vec<String>, Vec<Vec<u8>>, Repeated<Vec<u8>>) =
+            read_sections(input).unwrap();
+        assert_eq!(header, vec![String::from("seeds 1")]);
+        assert_eq!(numbers, vec![vec![1, 2], vec![3, 4]]);
+        assert_eq!(rest, vec![vec![5], vec![6]]);
+
+        let input = "1,2\r\n\r\n3\r\n4x\r\n";
+        let result: Result<(Vec<Vec<u8>>, Vec<u8>), _> = read_sections(input);
+        assert_eq!(location(result), Some(Location::new(4, 1)));
+
+        let result: Result<(Vec<u8>, Vec<Vec<u8>>), _> = read_sections("1\n\n1, 2, 300");
+        assert_eq!(location(result), Some(Location::new(3, 7)));
+
+        let result: Result<Vec<u8>, _> = read_sections("1\n\n2");
+        assert_eq!(location(result), Some(Location::new(3, 1)));
+
+        let result: Result<(Vec<u8>, Vec<u8>), _> = read_sections("1\n2");
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+}
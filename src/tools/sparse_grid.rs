@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use crate::tools::{Connectivity, FastMap, Matrix, Point};
+
+/// An unbounded grid that only stores the cells that were set, e.g. for maps that keep growing.
+/// Tracks the bounding box of its cells.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: FastMap<Point, T>,
+    /// The number of cells per column and row, so the bounds stay cheap when cells are removed.
+    columns: BTreeMap<i64, usize>,
+    rows: BTreeMap<i64, usize>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: FastMap::default(),
+            columns: BTreeMap::new(),
+            rows: BTreeMap::new(),
+        }
+    }
+}
+
+fn increment(counts: &mut BTreeMap<i64, usize>, key: i64) {
+    *counts.entry(key).or_insert(0) += 1;
+}
+
+fn decrement(counts: &mut BTreeMap<i64, usize>, key: i64) {
+    let count = counts.get_mut(&key).expect("every cell is counted");
+    *count -= 1;
+    if *count == 0 {
+        counts.remove(&key);
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    /// The cells of `matrix` that satisfy `keep`, with the top left cell at the origin.
+    pub fn from_matrix<F>(matrix: &Matrix<T>, keep: F) -> Self
+    where
+        T: Clone,
+        F: Fn(&T) -> bool,
+    {
+        matrix
+            .iter()
+            .filter(|(_, value)| keep(value))
+            .map(|(pos, value)| (Point::from(pos), value.clone()))
+            .collect()
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    /// Sets a cell, returning its previous value.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        let previous = self.cells.insert(point, value);
+        if previous.is_none() {
+            increment(&mut self.columns, point.x);
+            increment(&mut self.rows, point.y);
+        }
+        previous
+    }
+
+    /// Clears a cell, returning its value.
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let value = self.cells.remove(point)?;
+        decrement(&mut self.columns, point.x);
+        decrement(&mut self.rows, point.y);
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The top left and bottom right corner of the bounding box, both inclusive.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let (min_x, max_x) = (self.columns.first_key_value()?.0, self.columns.last_key_value()?.0);
+        let (min_y, max_y) = (self.rows.first_key_value()?.0, self.rows.last_key_value()?.0);
+        Some((Point::new(*min_x, *min_y), Point::new(*max_x, *max_y)))
+    }
+
+    /// The width of the bounding box.
+    pub fn width(&self) -> usize {
+        self.bounds().map_or(0, |(min, max)| (max.x - min.x + 1) as usize)
+    }
+
+    /// The height of the bounding box.
+    pub fn height(&self) -> usize {
+        self.bounds().map_or(0, |(min, max)| (max.y - min.y + 1) as usize)
+    }
+
+    /// All set cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    /// The points around `point`, whether set or not.
+    pub fn neighbours(point: Point, connectivity: Connectivity) -> impl Iterator<Item = Point> {
+        connectivity
+            .deltas()
+            .iter()
+            .map(move |delta| point + Point::from(*delta))
+    }
+
+    /// The bounding box as a matrix, with unset cells filled with `background`. The top left cell
+    /// of the matrix is the top left corner of [`SparseGrid::bounds`].
+    pub fn to_matrix(&self, background: T) -> Option<Matrix<T>>
+    where
+        T: Clone,
+    {
+        let (min, _) = self.bounds()?;
+        Some(Matrix::from_fn(self.width(), self.height(), |pos| {
+            let point = Point::from(pos) + min;
+            self.cells.get(&point).unwrap_or(&background).clone()
+        }))
+    }
+}
+
+/// Grids are equal if they have the same cells, the bounds follow from those.
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cells == other.cells
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+/// Renders the bounding box like [`Matrix`] does, with `.` for unset cells.
+impl<T> Display for SparseGrid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = self.bounds().unwrap_or((Point::ORIGIN, Point::ORIGIN));
+        writeln!(f, "SparseGrid {}×{} from {}", self.width(), self.height(), min)?;
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.cells.get(&Point::new(x, y)) {
+                    Some(value) => write!(f, "{}", value)?,
+                    None => write!(f, ".")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let matrix = Matrix::char_matrix_from_string("#..\n.#.\n..#").unwrap();
+        let mut grid = SparseGrid::from_matrix(&matrix, |c| *c == '#');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(2, 2))));
+        assert_eq!(grid.to_matrix('.'), Some(matrix.clone()));
+
+        grid.insert(Point::new(-2, 1), 'o');
+        assert_eq!(grid.bounds(), Some((Point::new(-2, 0), Point::new(2, 2))));
+        assert_eq!(grid.to_string(), "SparseGrid 5×3 from (x=-2, y=0)\n..#..\no..#.\n....#\n");
+
+        assert_eq!(grid.remove(&Point::new(-2, 1)), Some('o'));
+        assert_eq!(grid.remove(&Point::new(2, 2)), Some('#'));
+        assert_eq!(grid.remove(&Point::new(2, 2)), None);
+        assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(1, 1))));
+        assert_eq!(grid.get(&Point::new(1, 1)), Some(&'#'));
+        assert!(!grid.contains(&Point::new(1, 0)));
+
+        let neighbours: Vec<Point> = SparseGrid::<char>::neighbours(Point::ORIGIN, Connectivity::Four).collect();
+        assert_eq!(neighbours, vec![Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)]);
+
+        let empty: SparseGrid<char> = SparseGrid::new();
+        assert_eq!(empty.bounds(), None);
+        assert_eq!(empty.to_matrix('.'), None);
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+
+        // Removing every cell leaves no trace of the old bounds
+        for point in [Point::new(0, 0), Point::new(1, 1)] {
+            grid.remove(&point);
+        }
+        assert_eq!(grid, empty);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_string(), empty.to_string());
+        grid.insert(Point::new(5, 5), 'x');
+        assert_eq!(grid.bounds(), Some((Point::new(5, 5), Point::new(5, 5))));
+
+        // Overwriting a cell counts it once, so removing it once clears the bounds
+        grid.insert(Point::new(5, 5), 'y');
+        grid.remove(&Point::new(5, 5));
+        assert_eq!(grid.bounds(), None);
+    }
+}
@@ -0,0 +1,270 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::error::Error;
+use crate::tools::FastMap;
+use crate::visualize::{pixel_scale, render_pixels, Color, Frame, FrameSink};
+
+/// Collects frames into an animated, endlessly looping GIF which is written on `finish`.
+pub struct GifSink {
+    path: PathBuf,
+    // delay between frames in hundredths of a second
+    delay: u16,
+    width: u16,
+    height: u16,
+    images: Vec<Vec<u8>>,
+}
+
+impl GifSink {
+    pub fn new(path: PathBuf, delay: u16) -> Self {
+        GifSink {
+            path,
+            delay,
+            width: 0,
+            height: 0,
+            images: Vec::new(),
+        }
+    }
+}
+
+impl FrameSink for GifSink {
+    fn frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        let pixels = render_pixels(frame, pixel_scale(frame, 600));
+        let height = pixels.len();
+        let width = pixels.first().map_or(0, |row| row.len());
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(Error::solution(format!("frame of {width}x{height} pixels is too large for a GIF")));
+        }
+
+        self.width = self.width.max(width as u16);
+        self.height = self.height.max(height as u16);
+        self.images.push(encode_image(&pixels, self.delay));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        let mut gif = Vec::new();
+        gif.extend_from_slice(b"GIF89a");
+        gif.extend_from_slice(&self.width.to_le_bytes());
+        gif.extend_from_slice(&self.height.to_le_bytes());
+        // no global colour table, every frame brings its own
+        gif.extend_from_slice(&[0, 0, 0]);
+        // NETSCAPE2.0 extension, loop forever
+        gif.extend_from_slice(&[0x21, 0xff, 0x0b]);
+        gif.extend_from_slice(b"NETSCAPE2.0");
+        gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        for image in &self.images {
+            gif.extend_from_slice(image);
+        }
+        gif.push(0x3b);
+
+        fs::write(&self.path, gif)?;
+        println!("Wrote {} frames to {}", self.images.len(), self.path.display());
+        Ok(())
+    }
+}
+
+/// Maps every pixel to an index into a colour table. Frames with more than 256 distinct colours
+/// are reduced to a 6x6x6 colour cube.
+fn build_palette(rows: &[Vec<Color>]) -> (Vec<Color>, Vec<u8>) {
+    let mut palette: Vec<Color> = Vec::new();
+    let mut lookup: FastMap<Color, u8> = FastMap::default();
+    let mut indices = Vec::with_capacity(rows.len() * rows.first().map_or(0, |row| row.len()));
+
+    for color in rows.iter().flatten() {
+        if let Some(index) = lookup.get(color) {
+            indices.push(*index);
+        } else if palette.len() < 256 {
+            lookup.insert(*color, palette.len() as u8);
+            indices.push(palette.len() as u8);
+            palette.push(*color);
+        } else {
+            return cube_palette(rows);
+        }
+    }
+
+    (palette, indices)
+}
+
+fn cube_palette(rows: &[Vec<Color>]) -> (Vec<Color>, Vec<u8>) {
+    let level = |v: u8| (v as u16 * 5 + 127) / 255;
+    let palette = (0..216u16)
+        .map(|i| Color::rgb((i / 36 * 51) as u8, (i / 6 % 6 * 51) as u8, (i % 6 * 51) as u8))
+        .collect();
+    let indices = rows
+        .iter()
+        .flatten()
+        .map(|c| (level(c.r) * 36 + level(c.g) * 6 + level(c.b)) as u8)
+        .collect();
+    (palette, indices)
+}
+
+/// Encodes one frame: graphic control extension, image descriptor, local colour table and data.
+fn encode_image(rows: &[Vec<Color>], delay: u16) -> Vec<u8> {
+    let height = rows.len() as u16;
+    let width = rows.first().map_or(0, |row| row.len()) as u16;
+    let (palette, indices) = build_palette(rows);
+
+    // the colour table needs 2^(bits) entries
+    let bits = (palette.len().max(2) - 1).ilog2() as u8 + 1;
+
+    let mut out = vec![0x21, 0xf9, 0x04, 0x00];
+    out.extend_from_slice(&delay.to_le_bytes());
+    out.extend_from_slice(&[0x00, 0x00]);
+
+    out.push(0x2c);
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.push(0x80 | (bits - 1));
+    for index in 0..(1usize << bits) {
+        let Color { r, g, b } = palette.get(index).copied().unwrap_or(Color::BLACK);
+        out.extend_from_slice(&[r, g, b]);
+    }
+
+    let min_code_size = bits.max(2);
+    out.push(min_code_size);
+    for block in lzw_encode(&indices, min_code_size).chunks(255) {
+        out.push(block.len() as u8);
+        out.extend_from_slice(block);
+    }
+    out.push(0);
+    out
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// Variable code size LZW as used by GIF, resetting the dictionary once it is full.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter { bytes: Vec::new(), buffer: 0, bits: 0 };
+    let mut dictionary: FastMap<(u16, u8), u16> = FastMap::default();
+    let mut code_size = min_code_size + 1;
+    let mut next = end + 1;
+
+    writer.write(clear, code_size);
+
+    let mut iter = indices.iter();
+    let Some(first) = iter.next() else {
+        writer.write(end, code_size);
+        return writer.finish();
+    };
+    let mut current = *first as u16;
+
+    for index in iter {
+        if let Some(code) = dictionary.get(&(current, *index)) {
+            current = *code;
+            continue;
+        }
+
+        writer.write(current, code_size);
+        dictionary.insert((current, *index), next);
+        if next >= 1 << code_size {
+            code_size += 1;
+        }
+        next += 1;
+
+        if next == 4095 {
+            writer.write(clear, code_size);
+            dictionary.clear();
+            code_size = min_code_size + 1;
+            next = end + 1;
+        }
+        current = *index as u16;
+    }
+
+    writer.write(current, code_size);
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1u16 << min_code_size;
+        let reset = || (0..clear + 2).map(|c| vec![c as u8]).collect::<Vec<_>>();
+
+        let mut table = reset();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        let (mut buffer, mut bits, mut bytes) = (0u32, 0u8, data.iter());
+
+        loop {
+            while bits < code_size {
+                buffer |= (*bytes.next().unwrap() as u32) << bits;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << code_size) - 1)) as u16;
+            buffer >>= code_size;
+            bits -= code_size;
+
+            if code == clear {
+                table = reset();
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+
+            let entry = match (table.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) => [prev.as_slice(), &prev[..1]].concat(),
+                (None, None) => panic!("invalid first code"),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(prev) = previous {
+                table.push([prev.as_slice(), &entry[..1]].concat());
+                if table.len() == 1 << code_size && code_size < 12 {
+                    code_size += 1;
+                }
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw_roundtrip() {
+        let mut state = 12345u32;
+        let noise: Vec<u8> = (0..20_000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8 % 7
+            })
+            .collect();
+        let runs: Vec<u8> = (0..5000).map(|i| (i / 37 % 4) as u8).collect();
+
+        for (input, min_code_size) in [(noise, 3), (runs, 2), (vec![1], 2), (vec![], 2)] {
+            assert_eq!(lzw_decode(&lzw_encode(&input, min_code_size), min_code_size), input);
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Optional per-day visualisations.
+//!
+//! Days implementing [`Visualize`] push [`Frame`]s into a [`FrameSink`], which either animates them
+//! in the terminal or writes them out as PNG files or an animated GIF.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use crate::day::{Day, Part, RunType, YearDay};
+use crate::error::Error;
+use crate::tools::Matrix;
+
+mod gif;
+mod png;
+mod terminal;
+
+pub use gif::GifSink;
+pub use png::PngSink;
+pub use terminal::TerminalSink;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const GREY: Color = Color::rgb(90, 90, 90);
+    pub const RED: Color = Color::rgb(220, 50, 47);
+    pub const GREEN: Color = Color::rgb(80, 200, 80);
+    pub const BLUE: Color = Color::rgb(38, 139, 210);
+    pub const YELLOW: Color = Color::rgb(240, 200, 40);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    /// Linear interpolation between two colours, `t` is clamped to `0..=1`.
+    pub fn blend(self, other: Color, t: f64) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::rgb(mix(self.r, other.r), mix(self.g, other.g), mix(self.b, other.b))
+    }
+
+    /// A stable, reasonably distinct colour for an arbitrary id (e.g. a file id).
+    pub fn for_id(id: u64) -> Color {
+        // golden ratio hue stepping keeps neighbouring ids apart
+        let hue = (id as f64 * 0.618_033_988_749_895).fract() * 6.0;
+        let x = 1.0 - ((hue % 2.0) - 1.0).abs();
+        let (r, g, b) = match hue as u32 {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        let scale = |v: f64| (60.0 + v * 180.0) as u8;
+        Color::rgb(scale(r), scale(g), scale(b))
+    }
+}
+
+/// A single character cell of a frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cell {
+    pub glyph: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+impl Cell {
+    pub const EMPTY: Cell = Cell::new(' ', Color::WHITE);
+
+    pub const fn new(glyph: char, fg: Color) -> Self {
+        Cell { glyph, fg, bg: Color::BLACK }
+    }
+
+    pub const fn with_bg(mut self, bg: Color) -> Self {
+        self.bg = bg;
+        self
+    }
+
+    /// The colour used when a cell is rendered as a block of pixels.
+    pub fn pixel_color(&self) -> Color {
+        if self.glyph.is_whitespace() {
+            self.bg
+        } else {
+            self.fg
+        }
+    }
+}
+
+/// A grid of cells. Terminal output shows the glyphs, image output one coloured square per cell.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, fill: Cell) -> Self {
+        Frame {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_matrix<T, F>(matrix: &Matrix<T>, mapper: F) -> Self
+    where
+        F: Fn(&T) -> Cell,
+    {
+        let (width, height) = matrix.get_dimensions();
+        let mut frame = Frame::new(width, height, Cell::EMPTY);
+        for (pos, item) in matrix {
+            frame.set(pos.x, pos.y, mapper(item));
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Sets a cell; positions outside the frame are ignored.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = cell;
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1))
+    }
+}
+
+/// Receives the frames of a visualisation.
+pub trait FrameSink {
+    fn frame(&mut self, frame: &Frame) -> Result<(), Error>;
+
+    fn finish(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A day that can visualise how it solves its input.
+pub trait Visualize {
+    fn visualize(&self, input: &str, sink: &mut dyn FrameSink) -> Result<(), Error>;
+}
+
+/// Where the frames of a visualisation go, as selected by `--visualize`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Output {
+    Terminal,
+    Png(PathBuf),
+    Gif(PathBuf),
+}
+
+impl Output {
+    const DEFAULT_DIRECTORY: &'static str = "./visualizations";
+
+    /// Creates the sink for one day. Image outputs are written into the configured directory.
+    pub fn create_sink(&self, year_day: YearDay) -> Result<Box<dyn FrameSink>, Error> {
+        let YearDay { year, day } = year_day;
+        Ok(match self {
+            Output::Terminal => Box::new(TerminalSink::new(Duration::from_millis(60))),
+            Output::Png(dir) => {
+                let dir = dir.join(format!("{year}-day{day}"));
+                fs::create_dir_all(&dir)?;
+                Box::new(PngSink::new(dir))
+            }
+            Output::Gif(dir) => {
+                fs::create_dir_all(dir)?;
+                Box::new(GifSink::new(dir.join(format!("{year}-day{day}.gif")), 6))
+            }
+        })
+    }
+}
+
+impl FromStr for Output {
+    type Err = Error;
+
+    /// Parses `term`, `png[:dir]` or `gif[:dir]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, dir) = match s.split_once(':') {
+            Some((kind, dir)) => (kind, PathBuf::from(dir)),
+            None => (s, PathBuf::from(Self::DEFAULT_DIRECTORY)),
+        };
+
+        match kind {
+            "term" | "terminal" => Ok(Output::Terminal),
+            "png" => Ok(Output::Png(dir)),
+            "gif" => Ok(Output::Gif(dir)),
+            _ => Err(Error::parse(format!("unknown visualisation output '{s}'"))),
+        }
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Output::Terminal => write!(f, "term"),
+            Output::Png(dir) => write!(f, "png:{}", dir.display()),
+            Output::Gif(dir) => write!(f, "gif:{}", dir.display()),
+        }
+    }
+}
+
+/// Runs the visualisation of a day, if it has one. Returns whether anything was visualised.
+pub fn run(day: &dyn Day, output: &Output, run_type: &RunType) -> Result<bool, Error> {
+    let Some(visualizer) = day.visualizer() else {
+        return Ok(false);
+    };
+
+    let input = day
+        .get_input(run_type, &Part::Part1)
+        .ok_or(Error::MissingInput(day.get_year_and_date(), *run_type))?;
+
+    let mut sink = output.create_sink(day.get_year_and_date())?;
+    visualizer.visualize(&input, sink.as_mut())?;
+    sink.finish()?;
+    Ok(true)
+}
+
+/// Spreads `total` steps over at most `max_frames` frames, returning how many steps go in one.
+pub fn frame_stride(total: usize, max_frames: usize) -> usize {
+    total.div_ceil(max_frames.max(1)).max(1)
+}
+
+/// Collects frames in memory, mostly useful for tests.
+#[derive(Default)]
+pub struct FrameCollector {
+    pub frames: Vec<Frame>,
+}
+
+impl FrameSink for FrameCollector {
+    fn frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        self.frames.push(frame.clone());
+        Ok(())
+    }
+}
+
+/// Chooses how many pixels a cell gets so images end up roughly `target` pixels wide or high.
+fn pixel_scale(frame: &Frame, target: usize) -> usize {
+    (target / frame.width.max(frame.height).max(1)).clamp(1, 16)
+}
+
+/// Renders a frame to RGB pixel rows, each cell becoming a `scale`×`scale` square.
+fn render_pixels(frame: &Frame, scale: usize) -> Vec<Vec<Color>> {
+    let mut rows = Vec::with_capacity(frame.height * scale);
+    for row in frame.rows() {
+        let pixels: Vec<Color> = row
+            .iter()
+            .flat_map(|cell| std::iter::repeat_n(cell.pixel_color(), scale))
+            .collect();
+        for _ in 0..scale {
+            rows.push(pixels.clone());
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_output_from_str() {
+        assert_eq!("term".parse::<Output>().unwrap(), Output::Terminal);
+        assert_eq!("png:/tmp/x".parse::<Output>().unwrap(), Output::Png(PathBuf::from("/tmp/x")));
+        assert_eq!(
+            "gif".parse::<Output>().unwrap(),
+            Output::Gif(PathBuf::from(Output::DEFAULT_DIRECTORY))
+        );
+        assert!("bmp".parse::<Output>().is_err());
+    }
+
+    #[test]
+    fn test_render_pixels() {
+        let mut frame = Frame::new(2, 1, Cell::EMPTY);
+        frame.set(1, 0, Cell::new('#', Color::RED));
+        frame.set(5, 5, Cell::new('#', Color::RED));
+
+        let pixels = render_pixels(&frame, 2);
+        assert_eq!(pixels.len(), 2);
+        assert_eq!(pixels[1], vec![Color::BLACK, Color::BLACK, Color::RED, Color::RED]);
+    }
+}
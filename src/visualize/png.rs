@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::error::Error;
+use crate::visualize::{pixel_scale, render_pixels, Color, Frame, FrameSink};
+
+/// Writes every frame as a numbered PNG file into a directory.
+pub struct PngSink {
+    directory: PathBuf,
+    frames: usize,
+}
+
+impl PngSink {
+    pub fn new(directory: PathBuf) -> Self {
+        PngSink { directory, frames: 0 }
+    }
+}
+
+impl FrameSink for PngSink {
+    fn frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        let pixels = render_pixels(frame, pixel_scale(frame, 800));
+        let path = self.directory.join(format!("frame-{:05}.png", self.frames));
+        fs::write(path, encode(&pixels))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        println!("Wrote {} frames to {}", self.frames, self.directory.display());
+        Ok(())
+    }
+}
+
+/// Encodes RGB pixel rows as an 8-bit truecolour PNG.
+pub(crate) fn encode(rows: &[Vec<Color>]) -> Vec<u8> {
+    let height = rows.len() as u32;
+    let width = rows.first().map_or(0, |row| row.len()) as u32;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // bit depth 8, colour type 2 (RGB), default compression, filter and no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut raw = Vec::with_capacity(rows.len() * (1 + width as usize * 3));
+    for row in rows {
+        raw.push(0); // filter type none
+        for Color { r, g, b } in row {
+            raw.extend_from_slice(&[*r, *g, *b]);
+        }
+    }
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps data in a zlib stream of uncompressed deflate blocks. The frames are small enough
+/// that implementing actual compression isn't worth it.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+
+    let mut chunks = data.chunks(MAX_BLOCK).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        let is_last = chunks.peek().is_none();
+        let len = chunk.len() as u16;
+        out.push(is_last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_encode() {
+        let png = encode(&[vec![Color::RED, Color::BLUE]]);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[png.len() - 12..], &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]);
+    }
+}
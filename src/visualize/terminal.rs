@@ -0,0 +1,59 @@
+use std::fmt::Write as _;
+use std::io::{stdout, Write};
+use std::thread;
+use std::time::Duration;
+use crate::error::Error;
+use crate::visualize::{Color, Frame, FrameSink};
+
+/// Animates frames in the terminal using ANSI true colour escape sequences.
+pub struct TerminalSink {
+    delay: Duration,
+    frames: usize,
+}
+
+impl TerminalSink {
+    pub fn new(delay: Duration) -> Self {
+        TerminalSink { delay, frames: 0 }
+    }
+}
+
+fn push_color(out: &mut String, code: u8, color: Color) {
+    let Color { r, g, b } = color;
+    let _ = write!(out, "\x1b[{code};2;{r};{g};{b}m");
+}
+
+fn render(frame: &Frame) -> String {
+    let mut out = String::with_capacity(frame.width() * frame.height() * 24);
+    for row in frame.rows() {
+        let mut last = None;
+        for cell in row {
+            if last != Some((cell.fg, cell.bg)) {
+                push_color(&mut out, 38, cell.fg);
+                push_color(&mut out, 48, cell.bg);
+                last = Some((cell.fg, cell.bg));
+            }
+            out.push(cell.glyph);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+impl FrameSink for TerminalSink {
+    fn frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        let mut out = stdout().lock();
+        // Clear the screen once, afterwards only move the cursor home to avoid flickering
+        let prefix = if self.frames == 0 { "\x1b[2J\x1b[H" } else { "\x1b[H" };
+        write!(out, "{prefix}{}", render(frame))?;
+        out.flush()?;
+
+        self.frames += 1;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        println!("{} frames shown", self.frames);
+        Ok(())
+    }
+}